@@ -1,4 +1,8 @@
-use std::slice;
+use std::{
+    sync::{Mutex, mpsc},
+    thread,
+    time::{Duration, Instant},
+};
 
 use glam::DVec3;
 use raycaster::{
@@ -8,16 +12,249 @@ use raycaster::{
 };
 use raylib::prelude::*;
 
+/// Tempo padrão pra renderizar um frame enquanto a câmera se move, em milissegundos
+/// (muda com `--frame-budget=<ms>`)
+const DEFAULT_FRAME_BUDGET_MS: f64 = 33.0;
+
+/// Pedido de renderização de um frame pra thread de renderização
+struct FrameRequest {
+    /// Câmera do frame
+    camera: raycaster::Camera,
+    /// Token pra interromper o frame
+    cancel: CancellationToken,
+    /// Frames de prévia (enquanto a câmera se move) são renderizados na resolução dinâmica
+    /// e mostrados inteiros; os outros são renderizados na resolução da janela e mostrados
+    /// bloco a bloco, refinando a prévia
+    preview: bool,
+}
+
 fn main() {
+    // Argumentos: [cena.gltf] [--frame-budget=<ms>]
+    let mut scene_path = None;
+    let mut frame_budget = Duration::from_secs_f64(DEFAULT_FRAME_BUDGET_MS / 1000.0);
+    for arg in std::env::args().skip(1) {
+        match arg.strip_prefix("--frame-budget=") {
+            Some(ms) => {
+                // Só aceita tempos positivos e que cabem num Duration (sem NaN nem infinito)
+                frame_budget = ms
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|&ms| ms > 0.0)
+                    .and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok())
+                    .unwrap_or_else(|| {
+                        eprintln!("Tempo de frame inválido: {ms}");
+                        std::process::exit(1);
+                    })
+            }
+            None => scene_path = Some(arg),
+        }
+    }
+
     let (mut rl, thread) = raylib::init()
         .size(800, 450)
         .title("Hello, World")
         .log_level(TraceLogLevel::LOG_NONE)
         .build();
-    // rl.set_target_fps(60);
+    // A renderização roda em outra thread: limita o loop da janela (e a velocidade da câmera)
+    rl.set_target_fps(60);
+
+    // Carrega a cena de um arquivo glTF, se foi passado um, ou usa a cena de exemplo
+    let (scene, gltf_camera) = match scene_path {
+        Some(path) => loaders::load_gltf(&path).unwrap_or_else(|err| {
+            eprintln!("Erro ao carregar {path}: {err}");
+            std::process::exit(1);
+        }),
+        None => (example_scene(), None),
+    };
+    let mut camera = gltf_camera
+        .unwrap_or_else(|| raycaster::Camera::new(DVec3::new(0.0, 0.0, 0.0), 1.6, 0.9, 0.8));
+
+    // Cria um novo canvas para desenhar a cena
+    let mut canvas = Image::gen_image_color(800, 450, Color::BLACK);
+    canvas.set_format(PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8);
+    // Converte numa textura da GPU
+    let mut texture = rl.load_texture_from_image(&thread, &canvas).unwrap();
+    let (width, height) = (canvas.width as usize, canvas.height as usize);
+
+    // Pixels do frame mostrado na tela, atualizados pela thread de renderização
+    let frame = Mutex::new(vec![0_u8; width * height * 3]);
+    let scheduler = TileScheduler::new(32, TileOrder::Spiral);
+    let dynamic_resolution = Mutex::new(DynamicResolution::new(frame_budget));
+
+    thread::scope(|s| {
+        let (sender, receiver) = mpsc::channel::<FrameRequest>();
+        let (scene, frame, scheduler, dynamic_resolution) =
+            (&scene, &frame, &scheduler, &dynamic_resolution);
+        s.spawn(move || {
+            while let Ok(request) = receiver.recv() {
+                // Só o pedido mais recente importa
+                let request = receiver.try_iter().last().unwrap_or(request);
+                if !request.preview {
+                    // Os blocos vão direto pra tela, do centro pra fora
+                    request.camera.render_tiles(
+                        scene,
+                        width,
+                        height,
+                        scheduler,
+                        &request.cancel,
+                        |tile| tile.copy_to(&mut frame.lock().unwrap(), width),
+                    );
+                    continue;
+                }
+
+                // Renderiza a prévia num buffer com a resolução dinâmica e a amplia pra tela
+                let (preview_width, preview_height) =
+                    dynamic_resolution.lock().unwrap().resolution(width, height);
+                let preview = Mutex::new(vec![0_u8; preview_width * preview_height * 3]);
+                let start = Instant::now();
+                let finished = request.camera.render_tiles(
+                    scene,
+                    preview_width,
+                    preview_height,
+                    scheduler,
+                    &request.cancel,
+                    |tile| tile.copy_to(&mut preview.lock().unwrap(), preview_width),
+                );
+                if finished {
+                    dynamic_resolution.lock().unwrap().update(start.elapsed());
+                    upscale(
+                        &preview.into_inner().unwrap(),
+                        (preview_width, preview_height),
+                        &mut frame.lock().unwrap(),
+                        (width, height),
+                    );
+                }
+            }
+        });
+
+        // Com a resolução dinâmica ligada, a câmera em movimento gera prévias, e a imagem é
+        // refinada pra resolução da janela quando ela para. Desligada, todo frame é
+        // renderizado na resolução da janela. A tecla R liga e desliga.
+        let mut adaptive = true;
+        // Se o último pedido foi de prévia (e ainda falta refiná-lo)
+        let mut previewing = false;
+        let mut cancel = CancellationToken::new();
+        sender
+            .send(FrameRequest {
+                camera: camera.clone(),
+                cancel: cancel.clone(),
+                preview: false,
+            })
+            .unwrap();
+
+        while !rl.window_should_close() {
+            if rl.is_key_pressed(KeyboardKey::KEY_R) {
+                adaptive = !adaptive;
+            }
+
+            // Calcula o movimento usando WASD + Shift/Espaço
+            let movement_directions = [
+                (KeyboardKey::KEY_W, -camera.coord_system.z_axis),
+                (KeyboardKey::KEY_S, camera.coord_system.z_axis),
+                (KeyboardKey::KEY_A, -camera.coord_system.x_axis),
+                (KeyboardKey::KEY_D, camera.coord_system.x_axis),
+                (KeyboardKey::KEY_SPACE, camera.coord_system.y_axis),
+                (KeyboardKey::KEY_LEFT_SHIFT, -camera.coord_system.y_axis),
+            ];
+            let movement: DVec3 = movement_directions
+                .iter()
+                .filter(|(key, _)| rl.is_key_down(*key))
+                .map(|(_, dir)| *dir)
+                .sum();
+            let mut moved = false;
+            if movement != DVec3::ZERO {
+                camera.p0 += movement.normalize() * 0.1;
+                moved = true;
+            }
+
+            let rotation_directions = [
+                (KeyboardKey::KEY_LEFT, DVec3::Y),
+                (KeyboardKey::KEY_RIGHT, -DVec3::Y),
+                (KeyboardKey::KEY_UP, camera.coord_system.x_axis),
+                (KeyboardKey::KEY_DOWN, -camera.coord_system.x_axis),
+                (KeyboardKey::KEY_Q, camera.coord_system.z_axis),
+                (KeyboardKey::KEY_E, -camera.coord_system.z_axis),
+            ];
+            rotation_directions
+                .iter()
+                .for_each(|(key, axis)| {
+                    if rl.is_key_down(*key) {
+                        camera.rotate(*axis, 2_f64.to_radians());
+                        moved = true;
+                    }
+                });
 
-    // Criando os objetos da cena
-    let mut camera = raycaster::Camera::new(DVec3::new(0.0, 0.0, 0.0), 1.6, 0.9, 0.8);
+            // Se a câmera mexeu, pede um frame novo: uma prévia, com a resolução dinâmica
+            // ligada. Quando ela para, pede o refinamento da última prévia. Um refinamento em
+            // andamento é interrompido na hora, mas uma prévia termina (pra medir o tempo
+            // dela) e a thread de renderização passa direto pro pedido mais recente.
+            if moved || previewing {
+                if !previewing {
+                    cancel.cancel();
+                }
+                previewing = moved && adaptive;
+                cancel = CancellationToken::new();
+                sender
+                    .send(FrameRequest {
+                        camera: camera.clone(),
+                        cancel: cancel.clone(),
+                        preview: previewing,
+                    })
+                    .unwrap();
+            }
+
+            // Atualiza a textura da GPU com o frame atual
+            texture.update_texture(&frame.lock().unwrap()).unwrap();
+
+            // Desenha a textura na tela
+            let mut d = rl.begin_drawing(&thread);
+            d.clear_background(Color::BLACK);
+            d.draw_texture(&texture, 0, 0, Color::WHITE);
+            // Contador de FPS
+            d.draw_text(
+                format!("{}", d.get_fps()).as_str(),
+                10,
+                10,
+                20,
+                Color::WHITE,
+            );
+            // Estado da resolução dinâmica
+            let resolution_text = if adaptive {
+                let scale = dynamic_resolution.lock().unwrap().scale();
+                format!("Resolução dinâmica: {:.0}% (R desliga)", scale * 100.0)
+            } else {
+                "Resolução dinâmica desligada (R liga)".to_string()
+            };
+            d.draw_text(&resolution_text, 10, 35, 20, Color::WHITE);
+        }
+
+        // Interrompe o frame em andamento e fecha o canal, encerrando a thread de renderização
+        cancel.cancel();
+        drop(sender);
+    });
+}
+
+/// Amplia um frame RGB de `source_width` x `source_height` pixels pra `width` x `height`
+/// pixels, repetindo os pixels (vizinho mais próximo)
+fn upscale(
+    source: &[u8],
+    (source_width, source_height): (usize, usize),
+    target: &mut [u8],
+    (width, height): (usize, usize),
+) {
+    for y in 0..height {
+        let source_y = y * source_height / height;
+        for x in 0..width {
+            let source_x = x * source_width / width;
+            let from = (source_y * source_width + source_x) * 3;
+            let to = (y * width + x) * 3;
+            target[to..to + 3].copy_from_slice(&source[from..from + 3]);
+        }
+    }
+}
+
+/// Cena de exemplo, com alguns objetos e uma luz
+fn example_scene() -> Scene {
     let ball = Sphere::new(DVec3::new(-2.0, 2.0, -16.0), 4.0, raycaster::Material::GREEN);
 
     let cilinder = Cilinder::new(
@@ -42,77 +279,9 @@ fn main() {
     let plane = Plane::new(DVec3::new(0.0, -2.0, 0.0), DVec3::Y, raycaster::Material::WHITE);
 
     let light = Point::new(DVec3::new(0.0, 6.0, -10.0), DVec3::new(1.0, 0.65, 0.7), 0.5);
-    let scene = Scene {
-        objects: vec![Box::new(ball), Box::new(cilinder), Box::new(plane), Box::new(cone)],
-        lights: vec![Box::new(light)],
-        ambient_light: DVec3::splat(0.2),
-    };
-
-    // Cria um novo canvas para desenhar a cena
-    let mut canvas = Image::gen_image_color(800, 450, Color::BLACK);
-    canvas.set_format(PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8);
-    // Converte numa textura da GPU
-    let mut texture = rl.load_texture_from_image(&thread, &canvas).unwrap();
-    let pixel_data = unsafe {
-        slice::from_raw_parts_mut(
-            canvas.data() as *mut u8,
-            (canvas.width * canvas.height * 3) as usize,
-        )
-    };
-
-    while !rl.window_should_close() {
-        // Calcula o movimento usando WASD + Shift/Espaço
-        let movement_directions = [
-            (KeyboardKey::KEY_W, -camera.coord_system.z_axis),
-            (KeyboardKey::KEY_S, camera.coord_system.z_axis),
-            (KeyboardKey::KEY_A, -camera.coord_system.x_axis),
-            (KeyboardKey::KEY_D, camera.coord_system.x_axis),
-            (KeyboardKey::KEY_SPACE, camera.coord_system.y_axis),
-            (KeyboardKey::KEY_LEFT_SHIFT, -camera.coord_system.y_axis),
-        ];
-        let movement: DVec3 = movement_directions
-            .iter()
-            .filter(|(key, _)| rl.is_key_down(*key))
-            .map(|(_, dir)| *dir)
-            .sum();
-        if movement != DVec3::ZERO {
-            camera.p0 += movement.normalize() * 0.1;
-        }
-
-        let rotation_directions = [
-            (KeyboardKey::KEY_LEFT, DVec3::Y),
-            (KeyboardKey::KEY_RIGHT, -DVec3::Y),
-            (KeyboardKey::KEY_UP, camera.coord_system.x_axis),
-            (KeyboardKey::KEY_DOWN, -camera.coord_system.x_axis),
-            (KeyboardKey::KEY_Q, camera.coord_system.z_axis),
-            (KeyboardKey::KEY_E, -camera.coord_system.z_axis),
-        ];
-        rotation_directions
-            .iter()
-            .for_each(|(key, axis)| {
-                if rl.is_key_down(*key) {
-                    camera.rotate(*axis, 2_f64.to_radians());
-                }
-            });
-
-
-
-        // Renderiza o frame atual no canvas na CPU
-        camera.render_scene_to(&scene, &mut canvas);
-        // Atualiza a textura da GPU com os dados da imagem
-        texture.update_texture(pixel_data).unwrap();
-
-        // Desenha a textura na tela
-        let mut d = rl.begin_drawing(&thread);
-        d.clear_background(Color::BLACK);
-        d.draw_texture(&texture, 0, 0, Color::WHITE);
-        // Contador de FPS
-        d.draw_text(
-            format!("{}", d.get_fps()).as_str(),
-            10,
-            10,
-            20,
-            Color::WHITE,
-        );
-    }
+    Scene::new(
+        vec![Box::new(ball), Box::new(cilinder), Box::new(plane), Box::new(cone)],
+        vec![Box::new(light)],
+        DVec3::splat(0.2),
+    )
 }
@@ -0,0 +1,149 @@
+use glam::{BVec3, DMat4, DVec3};
+
+use crate::{Lanes, Ray, RayPacket};
+
+/// Caixa alinhada aos eixos (axis-aligned bounding box), definida pelas quinas
+/// de menor e de maior x, y e z
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    /// Quina com menor x, y e z
+    pub min: DVec3,
+    /// Quina com maior x, y e z
+    pub max: DVec3,
+}
+
+impl Aabb {
+    /// Caixa vazia (não contém nenhum ponto). É o elemento neutro da união.
+    pub const EMPTY: Aabb = Aabb {
+        min: DVec3::INFINITY,
+        max: DVec3::NEG_INFINITY,
+    };
+
+    /// Caixa que contém o espaço todo, usada por formas ilimitadas (como o `Plane`)
+    pub const INFINITE: Aabb = Aabb {
+        min: DVec3::NEG_INFINITY,
+        max: DVec3::INFINITY,
+    };
+
+    /// Construtor da caixa a partir das suas quinas
+    pub fn new(min: DVec3, max: DVec3) -> Aabb {
+        Aabb { min, max }
+    }
+
+    /// Menor caixa que contém todos os pontos (vazia se não há pontos)
+    pub fn from_points(points: impl IntoIterator<Item = DVec3>) -> Aabb {
+        points.into_iter().fold(Aabb::EMPTY, |aabb, p| {
+            Aabb::new(aabb.min.min(p), aabb.max.max(p))
+        })
+    }
+
+    /// Caixa com centro e metades das dimensões dadas
+    pub fn from_center(center: DVec3, half_extents: DVec3) -> Aabb {
+        Aabb::new(center - half_extents, center + half_extents)
+    }
+
+    /// Menor caixa que contém as duas caixas
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Região comum às duas caixas (pode ser vazia)
+    pub fn intersection(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.max(other.min), self.max.min(other.max))
+    }
+
+    /// Caixa aumentada de `margin` em todas as direções
+    pub fn padded(&self, margin: f64) -> Aabb {
+        Aabb::new(self.min - margin, self.max + margin)
+    }
+
+    /// Diz se a caixa não contém nenhum ponto
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    /// Diz se a caixa é limitada (não vazia e sem quinas no infinito ou NaN)
+    pub fn is_finite(&self) -> bool {
+        !self.is_empty() && self.min.is_finite() && self.max.is_finite()
+    }
+
+    /// Centro da caixa
+    pub fn center(&self) -> DVec3 {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Área da superfície da caixa (0 se ela é vazia)
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let size = self.max - self.min;
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    /// Caixa que contém esta caixa depois de uma transformação afim
+    /// (a caixa das 8 quinas transformadas). Caixas ilimitadas continuam ilimitadas.
+    pub fn transformed(&self, transform: DMat4) -> Aabb {
+        if self.is_empty() {
+            return Aabb::EMPTY;
+        }
+        if !self.is_finite() {
+            return Aabb::INFINITE;
+        }
+        Aabb::from_points((0..8).map(|corner| {
+            let p = DVec3::select(
+                BVec3::new(corner & 1 != 0, corner & 2 != 0, corner & 4 != 0),
+                self.max,
+                self.min,
+            );
+            transform.transform_point3(p)
+        }))
+    }
+
+    /// Retorna os t em que o raio entra e sai da caixa, recortados pro intervalo válido
+    /// do raio (None se ele não passa pela caixa nesse intervalo)
+    pub fn intersects(&self, ray: &Ray) -> Option<(f64, f64)> {
+        let mut t_enter = ray.t_min;
+        let mut t_exit = ray.t_max;
+        for axis in 0..3 {
+            if ray.dr[axis].abs() < 1e-12 {
+                if ray.p0[axis] < self.min[axis] || ray.p0[axis] > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (self.min[axis] - ray.p0[axis]) / ray.dr[axis];
+            let t2 = (self.max[axis] - ray.p0[axis]) / ray.dr[axis];
+            t_enter = t_enter.max(t1.min(t2));
+            t_exit = t_exit.min(t1.max(t2));
+        }
+        (t_enter <= t_exit).then_some((t_enter, t_exit))
+    }
+
+    /// `intersects` feito em todos os raios de um pacote ao mesmo tempo. Retorna o menor t
+    /// em que algum raio do pacote entra na caixa (None se nenhum passa por ela).
+    pub fn intersects_packet(&self, packet: &RayPacket) -> Option<f64> {
+        let mut t_enter = packet.t_min;
+        let mut t_exit = packet.t_max;
+        let [p0, dr] = [packet.p0, packet.dr].map(|v| v.to_array());
+        for axis in 0..3 {
+            let t1 = (Lanes::splat(self.min[axis]) - p0[axis]) / dr[axis];
+            let t2 = (Lanes::splat(self.max[axis]) - p0[axis]) / dr[axis];
+            // Raios paralelos ao eixo: ou estão entre os planos da caixa nesse eixo
+            // (e não têm limite), ou não passam pela caixa
+            let parallel = dr[axis].map(f64::abs).lt(1e-12);
+            let inside = p0[axis].within(self.min[axis], self.max[axis]);
+            let near = Lanes::select(
+                parallel,
+                Lanes::select(inside, Lanes::splat(f64::NEG_INFINITY), Lanes::INFINITY),
+                t1.min(t2),
+            );
+            let far = Lanes::select(parallel, Lanes::INFINITY, t1.max(t2));
+            t_enter = t_enter.max(near);
+            t_exit = t_exit.min(far);
+        }
+        let hit = t_enter.compare(t_exit, |t_enter, t_exit| t_enter <= t_exit);
+        Some(Lanes::select(hit, t_enter, Lanes::INFINITY).min_element())
+            .filter(|&t| t < f64::INFINITY)
+    }
+}
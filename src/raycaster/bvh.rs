@@ -0,0 +1,326 @@
+use glam::DVec3;
+
+use crate::{Aabb, Intersection, Lanes, PACKET_SIZE, Ray, RayPacket};
+
+/// Número de divisões do eixo usadas pra avaliar os planos de corte na construção
+const BINS: usize = 12;
+/// Número máximo de objetos que uma folha pode ter antes de ser dividida, mesmo que
+/// a heurística diga que não compensa
+const MAX_LEAF_SIZE: usize = 4;
+/// Custo de atravessar um nó, relativo ao custo de testar um objeto
+const TRAVERSAL_COST: f64 = 1.0;
+
+/// Hierarquia de caixas envolventes (bounding volume hierarchy) sobre uma lista de objetos,
+/// usada pra testar um raio só contra os objetos cujas caixas ele atravessa.
+///
+/// A árvore é construída pela heurística de área de superfície (SAH): cada nó é dividido
+/// no plano que minimiza a soma `área * número de objetos` dos dois lados, já que a chance
+/// de um raio acertar uma caixa é proporcional à área dela. Objetos com caixa ilimitada
+/// (como o `Plane`) ficam fora da árvore e são sempre testados.
+pub struct Bvh {
+    /// Nós da árvore, com o filho da esquerda de cada nó interno logo depois dele
+    nodes: Vec<BvhNode>,
+    /// Índices dos objetos, agrupados por folha
+    indices: Vec<usize>,
+    /// Índices dos objetos com caixa ilimitada
+    unbounded: Vec<usize>,
+    /// Número de objetos usados na construção
+    len: usize,
+}
+
+/// Nó da BVH
+struct BvhNode {
+    /// Caixa que envolve todos os objetos do nó
+    bounds: Aabb,
+    /// Em folhas, onde começam os objetos em `indices`.
+    /// Em nós internos, o índice do filho da direita.
+    start: usize,
+    /// Número de objetos da folha (0 em nós internos)
+    count: usize,
+}
+
+impl Bvh {
+    /// Constrói a hierarquia a partir das caixas dos objetos (o objeto i tem a caixa
+    /// `bounds[i]`). Objetos com caixa vazia nunca são testados.
+    pub fn new(bounds: &[Aabb]) -> Bvh {
+        let (indices, unbounded) = (0..bounds.len())
+            .filter(|&i| !bounds[i].is_empty())
+            .partition(|&i| bounds[i].is_finite());
+        let mut bvh = Bvh {
+            nodes: Vec::new(),
+            indices,
+            unbounded,
+            len: bounds.len(),
+        };
+        if !bvh.indices.is_empty() {
+            bvh.build(bounds, 0, bvh.indices.len());
+        }
+        bvh
+    }
+
+    /// Número de objetos usados na construção
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Diz se a hierarquia foi construída sem objetos
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Retorna a interseção mais próxima do raio (no intervalo dele), testando só os objetos
+    /// cujas caixas o raio atravessa. `intersects(i)` é a interseção do raio com o objeto i.
+    pub fn closest_hit<'a>(
+        &self,
+        ray: &Ray,
+        mut intersects: impl FnMut(usize) -> Option<Intersection<'a>>,
+    ) -> Option<Intersection<'a>> {
+        let mut closest: Option<Intersection<'a>> = None;
+        let mut test = |i: usize, closest: &mut Option<Intersection<'a>>| {
+            if let Some(intersection) = intersects(i)
+                && closest
+                    .as_ref()
+                    .is_none_or(|closest| intersection.t < closest.t)
+            {
+                *closest = Some(intersection);
+            }
+        };
+
+        for &i in &self.unbounded {
+            test(i, &mut closest);
+        }
+
+        // Pilha de nós a visitar, com o t em que o raio entra na caixa de cada um
+        let mut stack = Vec::new();
+        if let Some((t_enter, _)) = self
+            .nodes
+            .first()
+            .and_then(|root| root.bounds.intersects(ray))
+        {
+            stack.push((0, t_enter));
+        }
+        while let Some((node_index, t_enter)) = stack.pop() {
+            // Nós que começam depois da interseção mais próxima já encontrada não podem ter uma mais próxima
+            if closest.as_ref().is_some_and(|closest| t_enter > closest.t) {
+                continue;
+            }
+            let node = &self.nodes[node_index];
+            if node.count > 0 {
+                for &i in &self.indices[node.start..node.start + node.count] {
+                    test(i, &mut closest);
+                }
+                continue;
+            }
+
+            // Empilha o filho mais distante primeiro, pra visitar o mais próximo antes
+            let children = [node_index + 1, node.start].map(|child| {
+                let hit = self.nodes[child].bounds.intersects(ray);
+                (child, hit.map(|(t_enter, _)| t_enter))
+            });
+            let [near, far] = match (children[0].1, children[1].1) {
+                (Some(t0), Some(t1)) if t1 < t0 => [children[1], children[0]],
+                _ => children,
+            };
+            for (child, t_enter) in [far, near] {
+                if let Some(t_enter) = t_enter {
+                    stack.push((child, t_enter));
+                }
+            }
+        }
+        closest
+    }
+
+    /// `closest_hit` feito com um pacote de raios: as caixas são testadas com todos os raios
+    /// de uma vez, e um nó é visitado se algum raio passa pela caixa dele. `intersects(i, packet)`
+    /// são os t das interseções dos raios com o objeto i. Retorna o índice do objeto mais
+    /// próximo de cada raio, e termina o intervalo de cada raio que colide no t da interseção.
+    pub fn closest_hit_packet(
+        &self,
+        packet: &mut RayPacket,
+        mut intersects: impl FnMut(usize, &RayPacket) -> Lanes,
+    ) -> [Option<usize>; PACKET_SIZE] {
+        let mut closest = [None; PACKET_SIZE];
+        let mut test = |i: usize,
+                        packet: &mut RayPacket,
+                        closest: &mut [Option<usize>; PACKET_SIZE]| {
+            let hits = packet.shrink(intersects(i, packet));
+            for (closest, hit) in closest.iter_mut().zip(hits) {
+                if hit {
+                    *closest = Some(i);
+                }
+            }
+        };
+
+        for &i in &self.unbounded {
+            test(i, packet, &mut closest);
+        }
+
+        // Pilha de nós a visitar, com o menor t em que um raio entra na caixa de cada um
+        let mut stack = Vec::new();
+        if let Some(t_enter) = self
+            .nodes
+            .first()
+            .and_then(|root| root.bounds.intersects_packet(packet))
+        {
+            stack.push((0, t_enter));
+        }
+        while let Some((node_index, t_enter)) = stack.pop() {
+            // Nós que começam depois das interseções já encontradas de todos os raios não podem ter uma mais próxima
+            if t_enter >= packet.t_max.max_element() {
+                continue;
+            }
+            let node = &self.nodes[node_index];
+            if node.count > 0 {
+                for &i in &self.indices[node.start..node.start + node.count] {
+                    test(i, packet, &mut closest);
+                }
+                continue;
+            }
+
+            // Empilha o filho mais distante primeiro, pra visitar o mais próximo antes
+            let children = [node_index + 1, node.start]
+                .map(|child| (child, self.nodes[child].bounds.intersects_packet(packet)));
+            let [near, far] = match (children[0].1, children[1].1) {
+                (Some(t0), Some(t1)) if t1 < t0 => [children[1], children[0]],
+                _ => children,
+            };
+            for (child, t_enter) in [far, near] {
+                if let Some(t_enter) = t_enter {
+                    stack.push((child, t_enter));
+                }
+            }
+        }
+        closest
+    }
+
+    /// Diz se algum objeto cuja caixa o raio atravessa (no intervalo dele) satisfaz `hits(i)`,
+    /// parando no primeiro que satisfaz (a ordem em que os objetos são testados não é definida)
+    pub fn any_hit(&self, ray: &Ray, mut hits: impl FnMut(usize) -> bool) -> bool {
+        let crosses = |bounds: &Aabb| bounds.intersects(ray).is_some();
+        if self.unbounded.iter().any(|&i| hits(i)) {
+            return true;
+        }
+
+        let mut stack = Vec::new();
+        if self.nodes.first().is_some_and(|root| crosses(&root.bounds)) {
+            stack.push(0);
+        }
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if node.count > 0 {
+                if self.indices[node.start..node.start + node.count]
+                    .iter()
+                    .any(|&i| hits(i))
+                {
+                    return true;
+                }
+                continue;
+            }
+            for child in [node_index + 1, node.start] {
+                if crosses(&self.nodes[child].bounds) {
+                    stack.push(child);
+                }
+            }
+        }
+        false
+    }
+
+    /// Cria o nó com os objetos `indices[start..end]` (e, se compensar dividi-lo,
+    /// os nós abaixo dele). Retorna o índice do nó.
+    fn build(&mut self, bounds: &[Aabb], start: usize, end: usize) -> usize {
+        let node_bounds = self.indices[start..end]
+            .iter()
+            .fold(Aabb::EMPTY, |node_bounds, &i| node_bounds.union(&bounds[i]));
+        let node_index = self.nodes.len();
+        self.nodes.push(BvhNode {
+            bounds: node_bounds,
+            start,
+            count: end - start,
+        });
+
+        if let Some(middle) = self.split(bounds, start, end, &node_bounds) {
+            self.build(bounds, start, middle);
+            let right = self.build(bounds, middle, end);
+            let node = &mut self.nodes[node_index];
+            node.start = right;
+            node.count = 0;
+        }
+        node_index
+    }
+
+    /// Reordena os objetos `indices[start..end]` em dois grupos, separados pelo plano de
+    /// corte de menor custo, e retorna onde começa o segundo grupo. Os planos candidatos
+    /// dividem o intervalo dos centros das caixas em `BINS` partes iguais em cada eixo.
+    /// Retorna None se é melhor deixar os objetos todos numa folha.
+    fn split(
+        &mut self,
+        bounds: &[Aabb],
+        start: usize,
+        end: usize,
+        node_bounds: &Aabb,
+    ) -> Option<usize> {
+        let count = end - start;
+        if count <= 1 {
+            return None;
+        }
+        let centers =
+            Aabb::from_points(self.indices[start..end].iter().map(|&i| bounds[i].center()));
+
+        // Melhor corte encontrado: (custo, eixo, primeira divisão do lado direito)
+        let mut best: Option<(f64, usize, usize)> = None;
+        for axis in 0..3 {
+            if centers.max[axis] <= centers.min[axis] {
+                continue;
+            }
+            let mut bins = [(Aabb::EMPTY, 0_usize); BINS];
+            for &i in &self.indices[start..end] {
+                let bin = &mut bins[bin_index(&centers, axis, bounds[i].center())];
+                bin.0 = bin.0.union(&bounds[i]);
+                bin.1 += 1;
+            }
+
+            // Área e número de objetos do lado direito de cada plano, acumulados da direita pra esquerda
+            let mut right = [(0.0, 0_usize); BINS];
+            let mut accumulated = (Aabb::EMPTY, 0_usize);
+            for bin in (1..BINS).rev() {
+                accumulated = (
+                    accumulated.0.union(&bins[bin].0),
+                    accumulated.1 + bins[bin].1,
+                );
+                right[bin] = (accumulated.0.surface_area(), accumulated.1);
+            }
+            let mut left = (Aabb::EMPTY, 0_usize);
+            for bin in 1..BINS {
+                left = (left.0.union(&bins[bin - 1].0), left.1 + bins[bin - 1].1);
+                let (right_area, right_count) = right[bin];
+                if left.1 == 0 || right_count == 0 {
+                    continue;
+                }
+                let cost = left.0.surface_area() * left.1 as f64 + right_area * right_count as f64;
+                if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                    best = Some((cost, axis, bin));
+                }
+            }
+        }
+
+        let (cost, axis, bin) = best?;
+        // Custo esperado de dividir (relativo ao custo de testar um objeto) contra o de testar todos
+        let area = node_bounds.surface_area();
+        if count <= MAX_LEAF_SIZE && area > 0.0 && TRAVERSAL_COST + cost / area >= count as f64 {
+            return None;
+        }
+        self.indices[start..end]
+            .sort_by_key(|&i| bin_index(&centers, axis, bounds[i].center()) >= bin);
+        let left_count = self.indices[start..end]
+            .iter()
+            .take_while(|&&i| bin_index(&centers, axis, bounds[i].center()) < bin)
+            .count();
+        Some(start + left_count)
+    }
+}
+
+/// Divisão do eixo em que fica o centro de uma caixa
+fn bin_index(centers: &Aabb, axis: usize, center: DVec3) -> usize {
+    let extent = centers.max[axis] - centers.min[axis];
+    (((center[axis] - centers.min[axis]) / extent * BINS as f64) as usize).min(BINS - 1)
+}
@@ -1,13 +1,23 @@
-use std::slice;
+use std::{
+    array, slice,
+    sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
 
 use glam::{DMat3, DVec3, U8Vec3};
 use raylib::{color::Color, ffi::PixelFormat, texture::Image};
 use rayon::prelude::*;
 
-use crate::{transforms::rotation_matrix_from_axis_angle, Ray, Scene};
+use crate::{
+    transforms::rotation_matrix_from_axis_angle, CancellationToken, Intersection, PACKET_SIZE,
+    Ray, RayPacket, Scene, TileProgress, TileScheduler,
+};
 
 /// Camera com observador centrado em p0, sistema de coordenadas definido,
 /// e frame de rendering
+#[derive(Clone)]
 pub struct Camera {
     pub p0: DVec3,
     pub frame_width: f64,
@@ -57,65 +67,166 @@ impl Camera {
             panic!("Camera can only render to pixel format R8G8B8.");
         }
 
-        // Calcula o dx e o dy com base na resolução do Canvas
         let res_x = canvas.width;
         let res_y = canvas.height;
-        let dx = (self.frame_width / res_x as f64) * self.coord_system.x_axis;
-        let dy = -(self.frame_height / res_y as f64) * self.coord_system.y_axis;
-
-        // Centro do primeiro quadrado da grade de rendering
-        let p00 = self.frame_00() + dx / 2.0 + dy / 2.0;
+        let (p00, dx, dy) = self.pixel_grid(res_x as usize, res_y as usize);
 
         // Array de pixels bruto do canvas
         let pixel_data = unsafe {
             slice::from_raw_parts_mut(canvas.data() as *mut u8, (res_x * res_y * 3) as usize)
         };
 
-        // Renderiza cada pixel (cada trio de u8 em pixel_data)
+        // Renderiza uma linha por vez; os pixels de cada linha são testados em grupos de
+        // PACKET_SIZE vizinhos, cujos raios são testados juntos como um pacote
         pixel_data
-            .par_chunks_mut(3) // Renderiza em paralelo usando a biblioteca Rayon
+            .par_chunks_mut(3 * res_x as usize) // Renderiza em paralelo usando a biblioteca Rayon
             .enumerate()
-            .for_each(|(i, pixel)| {
-                // Pixel (px,py) atual
-                let px = (i % res_x as usize) as f64;
-                let py = (i / res_x as usize) as f64;
-
-                // Calcula a direção do próximo raio com base no pixel atual e p00
-                let p_target = p00 + px * dx + py * dy;
-                let ray_dr = (p_target - self.p0).normalize();
-                let ray = Ray::new(self.p0, ray_dr);
-
-                // Pega a interseção mais próxima
-                let closest_intersection = &scene
-                    .objects
-                    .iter()
-                    .filter_map(|object| object.intersects(&ray)) // Só as interseções que não são "None"
-                    .min_by(|intersection1, intersection2| {
-                        intersection1.t.total_cmp(&intersection2.t)
+            .for_each(|(row, row_pixels)| {
+                let py = row as f64;
+                for (group, pixels) in row_pixels.chunks_mut(3 * PACKET_SIZE).enumerate() {
+                    let rays: [Ray; PACKET_SIZE] = array::from_fn(|k| {
+                        // Pixel (px,py) atual
+                        let px = (group * PACKET_SIZE + k) as f64;
+
+                        // Calcula a direção do próximo raio com base no pixel atual e p00
+                        let p_target = p00 + px * dx + py * dy;
+                        Ray::new(self.p0, (p_target - self.p0).normalize())
                     });
+                    // O último grupo da linha pode ter menos pixels
+                    self.trace_packet(scene, &rays[..pixels.len() / 3], pixels);
+                }
+            });
+    }
 
-                // Se houve interseção, pinta a cor da iluminação nela
-                // Se não, pinta o pixel de preto.
-                let mut total_light = U8Vec3::ZERO;
-                if let Some(intersection) = closest_intersection {
-                    // Iluminação "passiva" - luz ambiente
-                    let passive = scene.ambient_light * intersection.material.k_amb;
-                    // Iluminação ativa - as luzes definidas do cenário
-                    let active: DVec3 = scene
-                        .lights
-                        .iter()
-                        .map(|light| light.color_at(&intersection, ray_dr, &scene))
-                        .sum();
-                    // Converte pra um número entre 0 e 255 pro valor de cor
-                    total_light = ((passive + active) * 255.0)
-                        .min(DVec3::splat(255.0)) // A cor deve ser no máximo 255
-                        .as_u8vec3();
+    /// Renderiza a cena em blocos, num frame de `width` x `height` pixels, na ordem definida
+    /// pelo escalonador. Cada thread do Rayon pega o próximo bloco da fila quando termina o
+    /// anterior, e `on_tile` é chamada (na thread que o renderizou) com cada bloco terminado.
+    /// O token é checado antes de cada bloco: depois de cancelado, os blocos em andamento
+    /// terminam e os outros não são renderizados.
+    /// Retorna true se o frame foi renderizado inteiro, false se foi interrompido.
+    pub fn render_tiles(
+        &self,
+        scene: &Scene,
+        width: usize,
+        height: usize,
+        scheduler: &TileScheduler,
+        cancel: &CancellationToken,
+        on_tile: impl Fn(TileProgress) + Sync,
+    ) -> bool {
+        let tiles = scheduler.tiles(width, height);
+        let (p00, dx, dy) = self.pixel_grid(width, height);
+        // Próximo bloco da fila e número de blocos terminados
+        let next = AtomicUsize::new(0);
+        let finished = AtomicUsize::new(0);
+
+        rayon::broadcast(|_| {
+            while !cancel.is_cancelled() {
+                let Some(&tile) = tiles.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                    break;
+                };
+                let mut pixels = vec![0; tile.width * tile.height * 3];
+                for (row, row_pixels) in pixels.chunks_exact_mut(tile.width * 3).enumerate() {
+                    let py = (tile.y + row) as f64;
+                    // Grupos de PACKET_SIZE pixels vizinhos na linha, testados como um pacote
+                    for (group, group_pixels) in row_pixels.chunks_mut(3 * PACKET_SIZE).enumerate()
+                    {
+                        let rays: [Ray; PACKET_SIZE] = array::from_fn(|k| {
+                            let px = (tile.x + group * PACKET_SIZE + k) as f64;
+                            let p_target = p00 + px * dx + py * dy;
+                            Ray::new(self.p0, (p_target - self.p0).normalize())
+                        });
+                        self.trace_packet(scene, &rays[..group_pixels.len() / 3], group_pixels);
+                    }
                 }
+                on_tile(TileProgress {
+                    tile,
+                    pixels: &pixels,
+                    finished: finished.fetch_add(1, Ordering::Relaxed) + 1,
+                    total: tiles.len(),
+                });
+            }
+        });
+        finished.into_inner() == tiles.len()
+    }
 
-                pixel[0] = total_light.x;
-                pixel[1] = total_light.y;
-                pixel[2] = total_light.z;
-            });
+    /// Desenha a cena em blocos para um canvas (struct imagem do raylib), chamando
+    /// `progress` a cada bloco terminado (depois de copiá-lo pro canvas).
+    /// Retorna true se o frame foi renderizado inteiro (ver `render_tiles`).
+    pub fn render_tiles_to(
+        &self,
+        scene: &Scene,
+        canvas: &mut Image,
+        scheduler: &TileScheduler,
+        cancel: &CancellationToken,
+        progress: impl Fn(TileProgress) + Sync,
+    ) -> bool {
+        if canvas.format != PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8 as i32 {
+            panic!("Camera can only render to pixel format R8G8B8.");
+        }
+
+        let res_x = canvas.width as usize;
+        let res_y = canvas.height as usize;
+        // Array de pixels bruto do canvas
+        let pixel_data =
+            unsafe { slice::from_raw_parts_mut(canvas.data() as *mut u8, res_x * res_y * 3) };
+        let pixel_data = Mutex::new(pixel_data);
+
+        self.render_tiles(scene, res_x, res_y, scheduler, cancel, |tile| {
+            tile.copy_to(&mut pixel_data.lock().unwrap(), res_x);
+            progress(tile);
+        })
+    }
+
+    /// Retorna o centro do pixel (0, 0) do frame e os deslocamentos entre pixels vizinhos
+    /// em x e em y, pra um frame de `res_x` x `res_y` pixels
+    fn pixel_grid(&self, res_x: usize, res_y: usize) -> (DVec3, DVec3, DVec3) {
+        // Calcula o dx e o dy com base na resolução do Canvas
+        let dx = (self.frame_width / res_x as f64) * self.coord_system.x_axis;
+        let dy = -(self.frame_height / res_y as f64) * self.coord_system.y_axis;
+
+        // Centro do primeiro quadrado da grade de rendering
+        let p00 = self.frame_00() + dx / 2.0 + dy / 2.0;
+        (p00, dx, dy)
+    }
+
+    /// Cores (entre 0 e 255) vistas por 1 a `PACKET_SIZE` raios saindo da câmera, testados
+    /// juntos como um pacote. As cores são escritas em `pixels`, 3 bytes por raio.
+    fn trace_packet(&self, scene: &Scene, rays: &[Ray], pixels: &mut [u8]) {
+        // Pega a interseção mais próxima de cada raio
+        let intersections = scene.intersects_packet(&RayPacket::new(rays));
+        for ((pixel, ray), intersection) in
+            pixels.chunks_exact_mut(3).zip(rays).zip(&intersections)
+        {
+            pixel.copy_from_slice(&self.shade(scene, ray, intersection).to_array());
+        }
+    }
+
+    /// Cor (entre 0 e 255) vista por um raio saindo da câmera, dada a interseção mais
+    /// próxima dele com a cena
+    fn shade(
+        &self,
+        scene: &Scene,
+        ray: &Ray,
+        closest_intersection: &Option<Intersection>,
+    ) -> U8Vec3 {
+        // Se houve interseção, pinta a cor da iluminação nela
+        // Se não, pinta o pixel de preto.
+        let mut total_light = U8Vec3::ZERO;
+        if let Some(intersection) = closest_intersection {
+            // Iluminação "passiva" - luz ambiente
+            let passive = scene.ambient_light * intersection.material.k_amb;
+            // Iluminação ativa - as luzes definidas do cenário
+            let active: DVec3 = scene
+                .lights
+                .iter()
+                .map(|light| light.color_at(intersection, ray.dr, scene))
+                .sum();
+            // Converte pra um número entre 0 e 255 pro valor de cor
+            total_light = ((passive + active) * 255.0)
+                .min(DVec3::splat(255.0)) // A cor deve ser no máximo 255
+                .as_u8vec3();
+        }
+        total_light
     }
 
     /// Gira a câmera ao redor de um eixo
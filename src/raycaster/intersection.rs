@@ -10,8 +10,15 @@ pub struct Intersection<'a> {
     pub p: DVec3,
     /// Vetor normal da interseção
     pub normal: DVec3,
+    /// Normal geométrica da superfície no ponto (nos triângulos, a normal da face, sem
+    /// interpolar as normais dos vértices). É usada pra afastar a origem dos raios
+    /// secundários da superfície.
+    pub geometric_normal: DVec3,
     /// Material no ponto de interseção
     pub material: Material,
+    /// Coordenadas baricêntricas (w0, w1, w2) do ponto no triângulo atingido
+    /// (`None` se o objeto não é feito de triângulos)
+    pub barycentric: Option<DVec3>,
     /// Objeto colidido
     pub object: &'a dyn Shape
 }
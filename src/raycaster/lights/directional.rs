@@ -0,0 +1,41 @@
+use glam::DVec3;
+
+use crate::{
+    lights::{is_shadowed, phong, Light},
+    Intersection, Scene,
+};
+
+/// Luz direcional (como a do sol): vem de infinitamente longe, sempre na mesma direção
+pub struct Directional {
+    /// Direção para onde a luz vai (unitária)
+    pub direction: DVec3,
+    /// Cor da luz
+    pub color: DVec3,
+    /// Intensidade da luz
+    pub intensity: f64,
+}
+
+impl Directional {
+    /// Construtor da luz direcional
+    pub fn new(direction: DVec3, color: DVec3, intensity: f64) -> Directional {
+        Directional { direction: direction.normalize(), color, intensity }
+    }
+
+    /// Calcula a intensidade/cor da luz em um determinado ponto de interseção numa cena
+    /// `v: vetor unitário do ponto de interseção em direção ao observador`
+    pub fn color_at(&self, intersection: &Intersection, v: DVec3, scene: &Scene) -> DVec3 {
+        let light_direction = -self.direction;
+
+        if is_shadowed(scene, intersection, light_direction, f64::INFINITY) {
+            DVec3::ZERO
+        } else {
+            phong(intersection, light_direction, v, self.color * self.intensity)
+        }
+    }
+}
+
+impl Light for Directional {
+    fn color_at(&self, intersection: &Intersection, v: DVec3, scene: &Scene) -> DVec3 {
+        self.color_at(intersection, v, scene)
+    }
+}
@@ -1,13 +1,46 @@
-use crate::{Intersection, Scene};
+use crate::{Intersection, Ray, Scene};
 use glam::DVec3;
 
+mod directional;
 mod point;
+mod spot;
 
+pub use directional::Directional;
 pub use point::Point;
+pub use spot::Spot;
 
 /// """Classe""" 'luz'
 pub trait Light: Sync {
     /// Calcula a intensidade/cor da luz em um determinado ponto de interseção numa cena
     /// `v: vetor unitário do ponto de interseção em direção ao observador`
     fn color_at(&self, intersection: &Intersection, v: DVec3, scene: &Scene) -> DVec3;
-}
\ No newline at end of file
+}
+
+/// Testa se existe um objeto da cena entre o ponto iluminado e a luz,
+/// pra ver se o ponto está na sombra
+/// `l: vetor unitário do ponto em direção à luz`
+/// `distance: distância do ponto até a luz (infinita nas luzes direcionais)`
+pub(crate) fn is_shadowed(scene: &Scene, intersection: &Intersection, l: DVec3, distance: f64) -> bool {
+    let light_ray = Ray::from_surface(intersection.p, intersection.geometric_normal, l)
+        .with_interval(0.0, distance);
+    scene.is_occluded(&light_ray)
+}
+
+/// Reflexão difusa + especular (modelo de Phong) de uma luz que chega no ponto de interseção
+/// `l: vetor unitário do ponto de interseção em direção à luz`
+/// `v: vetor unitário do ponto de interseção em direção ao observador`
+pub(crate) fn phong(intersection: &Intersection, l: DVec3, v: DVec3, light_intensity: DVec3) -> DVec3 {
+    let n = intersection.normal;
+    let mat = intersection.material;
+
+    let r = 2.0 * l.dot(n) * n - l; // vetor l refletido na normal
+    let nl = n.dot(l); // normal escalar l
+    let rv = r.dot(-v); // r escalar v
+
+    // O check > 0.0 previne o bug de iluminação no "lado escuro" do objeto
+    let mut ieye = DVec3::ZERO;
+    if nl > 0.0 { ieye += mat.k_dif * nl * light_intensity; } // Reflexão difusa
+    if rv > 0.0 { ieye += mat.k_esp * rv.powf(mat.e) * light_intensity; } // Reflexão especular
+
+    ieye
+}
@@ -1,6 +1,9 @@
 use glam::DVec3;
 
-use crate::{lights::Light, Intersection, Scene, Ray};
+use crate::{
+    lights::{is_shadowed, phong, Light},
+    Intersection, Scene,
+};
 
 /// Luz pontual definida por sua posição e sua intensidade
 pub struct Point {
@@ -22,34 +25,12 @@ impl Point {
     /// `v: vetor unitário do ponto de interseção em direção ao observador`
     pub fn color_at(&self, intersection: &Intersection, v: DVec3, scene: &Scene) -> DVec3 {
         let light_direction = (self.pos - intersection.p).normalize();
-        let light_ray = Ray::new(intersection.p, light_direction);
-        
-        // Testa se existe um objeto entre o objeto renderizado e a luz
-        // pra ver se tem sombra
-        let shadow_intersection = scene.objects.iter().find(|object| {
-            // Checa se a interseção existe e se ela está dentro de um threshold de erro    
-            let intersection = object.intersects(&light_ray);
-            intersection.is_some() && intersection.unwrap().t.abs() > 1e-6
-        });
+        let light_distance = self.pos.distance(intersection.p);
 
-        if shadow_intersection.is_none() {
-            let l = light_direction; // vetor unitário apontando na direção da luz
-            let n = intersection.normal;
-            let mat = intersection.material;
-            let light_intensity = self.color * self.intensity;
-                    
-            let r = 2.0 * l.dot(n)*n - l; // vetor l refletido na normal
-            let nl = n.dot(l); // normal escalar l
-            let rv = r.dot(-v); // r escalar v
-
-            // O check > 0.0 previne o bug de iluminação no "lado escuro" do objeto
-            let mut ieye = DVec3::ZERO;
-            if nl > 0.0 { ieye += mat.k_dif * nl * light_intensity; } // Reflexão difusa
-            if rv > 0.0 { ieye += mat.k_esp * rv.powf(mat.e) * light_intensity; } // Reflexão especular
-            
-            ieye
-        } else {
+        if is_shadowed(scene, intersection, light_direction, light_distance) {
             DVec3::ZERO
+        } else {
+            phong(intersection, light_direction, v, self.color * self.intensity)
         }
     }
 }
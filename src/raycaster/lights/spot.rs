@@ -0,0 +1,70 @@
+use glam::DVec3;
+
+use crate::{
+    lights::{is_shadowed, phong, Light},
+    Intersection, Scene,
+};
+
+/// Luz spot: uma luz pontual que só ilumina dentro de um cone. A intensidade é total até
+/// o ângulo `inner_angle` com o eixo do cone e cai suavemente até zero em `outer_angle`.
+pub struct Spot {
+    /// Posição da luz (ponta do cone)
+    pub pos: DVec3,
+    /// Direção do eixo do cone (unitária)
+    pub direction: DVec3,
+    /// Ângulo (em radianos) onde a intensidade começa a cair
+    pub inner_angle: f64,
+    /// Ângulo (em radianos) onde a intensidade chega a zero
+    pub outer_angle: f64,
+    /// Cor da luz
+    pub color: DVec3,
+    /// Intensidade da luz
+    pub intensity: f64,
+}
+
+impl Spot {
+    /// Construtor da luz spot
+    pub fn new(
+        pos: DVec3,
+        direction: DVec3,
+        inner_angle: f64,
+        outer_angle: f64,
+        color: DVec3,
+        intensity: f64,
+    ) -> Spot {
+        Spot { pos, direction: direction.normalize(), inner_angle, outer_angle, color, intensity }
+    }
+
+    /// Fator de 0 a 1 da intensidade da luz na direção `-light_direction`
+    /// (mesma queda da extensão KHR_lights_punctual do glTF)
+    fn falloff(&self, light_direction: DVec3) -> f64 {
+        let cos_inner = self.inner_angle.cos();
+        let cos_outer = self.outer_angle.cos();
+        let cos_angle = self.direction.dot(-light_direction);
+        let factor = ((cos_angle - cos_outer) / (cos_inner - cos_outer).max(1e-3)).clamp(0.0, 1.0);
+        factor * factor
+    }
+
+    /// Calcula a intensidade/cor da luz em um determinado ponto de interseção numa cena
+    /// `v: vetor unitário do ponto de interseção em direção ao observador`
+    pub fn color_at(&self, intersection: &Intersection, v: DVec3, scene: &Scene) -> DVec3 {
+        let light_direction = (self.pos - intersection.p).normalize();
+        let falloff = self.falloff(light_direction);
+        if falloff <= 0.0 {
+            return DVec3::ZERO;
+        }
+        let light_distance = self.pos.distance(intersection.p);
+
+        if is_shadowed(scene, intersection, light_direction, light_distance) {
+            DVec3::ZERO
+        } else {
+            phong(intersection, light_direction, v, self.color * self.intensity * falloff)
+        }
+    }
+}
+
+impl Light for Spot {
+    fn color_at(&self, intersection: &Intersection, v: DVec3, scene: &Scene) -> DVec3 {
+        self.color_at(intersection, v, scene)
+    }
+}
@@ -0,0 +1,292 @@
+use std::{fs, path::Path};
+
+use ::gltf::{Gltf, buffer, camera::Projection, khr_lights_punctual::Kind, mesh::Mode};
+use glam::{DMat3, DMat4, DVec3, Mat4};
+
+use crate::{
+    Aabb, Camera, Material, Scene,
+    lights::{Directional, Light, Point, Spot},
+    loaders::LoadError,
+    shapes::{Shape, TriangleMesh},
+    transforms::normal_matrix,
+};
+
+/// Proporção largura/altura usada em câmeras que não definem `aspectRatio`
+const DEFAULT_ASPECT_RATIO: f64 = 16.0 / 9.0;
+/// Menor distância usada no inverso do quadrado, pra luzes (quase) no centro da cena
+const MIN_LIGHT_DISTANCE: f64 = 1e-3;
+/// Exposição fixa das luzes importadas: a iluminância (em lux) que vira intensidade 1 nas
+/// luzes de Phong. 1000 lux é a luz de um dia nublado, e fica perto da iluminância das
+/// luzes padrão dos editores (um sol de 1 W/m² tem 683 lux).
+const REFERENCE_ILLUMINANCE: f64 = 1000.0;
+
+/// Carrega uma cena de um arquivo glTF 2.0 (.gltf, com os buffers externos procurados
+/// na mesma pasta, ou .glb). Retorna a cena e a primeira câmera (perspectiva) dela.
+pub fn load_gltf(path: impl AsRef<Path>) -> Result<(Scene, Option<Camera>), LoadError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path)?;
+    parse_gltf_in(&bytes, path.parent())
+}
+
+/// Lê o conteúdo de um arquivo glTF 2.0 (.gltf ou .glb) que não referencia arquivos
+/// externos (os buffers ficam no .glb ou em URIs `data:`). Ver `load_gltf`.
+pub fn parse_gltf(bytes: &[u8]) -> Result<(Scene, Option<Camera>), LoadError> {
+    parse_gltf_in(bytes, None)
+}
+
+/// Monta a cena a partir do glTF. A cena usada é a padrão do arquivo (ou a primeira).
+///
+/// - Cada primitiva de triângulos vira uma `TriangleMesh`, com a transformação acumulada
+///   dos nós aplicada nos vértices. Normais que faltam são calculadas, e as cores dos
+///   vértices (`COLOR_0`) são mantidas.
+/// - O material PBR metallic-roughness é aproximado por um `Material` de Phong
+///   (ver `approximate_material`). Texturas são ignoradas.
+/// - As luzes da extensão KHR_lights_punctual viram `Point`, `Spot` ou `Directional`.
+///   As intensidades físicas delas são convertidas pras das luzes de Phong
+///   (ver `approximate_lights`).
+/// - A primeira câmera perspectiva encontrada vira a `Camera`. Câmeras ortográficas
+///   não são suportadas.
+fn parse_gltf_in(bytes: &[u8], base: Option<&Path>) -> Result<(Scene, Option<Camera>), LoadError> {
+    let Gltf { document, blob } = Gltf::from_slice(bytes)?;
+    let buffers = ::gltf::import_buffers(&document, base, blob)?;
+
+    let mut importer = Importer {
+        buffers: &buffers,
+        objects: Vec::new(),
+        lights: Vec::new(),
+        camera: None,
+    };
+    if let Some(scene) = document
+        .default_scene()
+        .or_else(|| document.scenes().next())
+    {
+        for node in scene.nodes() {
+            importer.visit(node, DMat4::IDENTITY)?;
+        }
+    }
+
+    let bounds = importer
+        .objects
+        .iter()
+        .fold(Aabb::EMPTY, |bounds, object| bounds.union(&object.aabb()));
+    // Sem objetos limitados, a cena não tem centro: usa a origem
+    let center = if bounds.is_finite() {
+        bounds.center()
+    } else {
+        DVec3::ZERO
+    };
+    let lights = approximate_lights(importer.lights, center);
+    let scene = Scene::new(importer.objects, lights, DVec3::splat(0.2));
+    Ok((scene, importer.camera))
+}
+
+/// Estado da importação enquanto os nós da cena são percorridos
+struct Importer<'a> {
+    buffers: &'a [buffer::Data],
+    objects: Vec<Box<dyn Shape>>,
+    lights: Vec<PunctualLight>,
+    camera: Option<Camera>,
+}
+
+/// Luz da extensão KHR_lights_punctual lida do arquivo. A intensidade só é convertida
+/// no fim da importação, quando a posição dos objetos é conhecida.
+struct PunctualLight {
+    kind: PunctualKind,
+    position: DVec3,
+    forward: DVec3,
+    color: DVec3,
+    /// Intensidade do glTF: em candela nas luzes pontuais e spot, em lux nas direcionais
+    intensity: f64,
+}
+
+/// Tipo de uma `PunctualLight`
+enum PunctualKind {
+    Directional,
+    Point,
+    /// Luz spot, com os ângulos (em radianos) do cone interno e do externo
+    Spot(f64, f64),
+}
+
+impl Importer<'_> {
+    /// Importa o nó e seus filhos. `parent` é a transformação acumulada dos nós acima dele.
+    fn visit(&mut self, node: ::gltf::Node, parent: DMat4) -> Result<(), LoadError> {
+        let transform = parent * Mat4::from_cols_array_2d(&node.transform().matrix()).as_dmat4();
+
+        if let Some(mesh) = node.mesh() {
+            for primitive in mesh.primitives() {
+                if let Some(mesh) = self.primitive_mesh(&primitive, transform)? {
+                    self.objects.push(Box::new(mesh));
+                }
+            }
+        }
+
+        // Luzes e câmeras apontam para o -z local do nó
+        let position = transform.transform_point3(DVec3::ZERO);
+        let forward = transform.transform_vector3(-DVec3::Z).normalize();
+        if let Some(light) = node.light() {
+            self.lights.push(PunctualLight {
+                kind: match light.kind() {
+                    Kind::Directional => PunctualKind::Directional,
+                    Kind::Point => PunctualKind::Point,
+                    Kind::Spot {
+                        inner_cone_angle,
+                        outer_cone_angle,
+                    } => PunctualKind::Spot(inner_cone_angle as f64, outer_cone_angle as f64),
+                },
+                position,
+                forward,
+                color: DVec3::from_array(light.color().map(f64::from)),
+                intensity: light.intensity() as f64,
+            });
+        }
+
+        if let (None, Some(camera)) = (&self.camera, node.camera())
+            && let Projection::Perspective(perspective) = camera.projection()
+        {
+            // O frame fica a uma unidade do observador, com a altura dada pelo campo de visão
+            let frame_height = 2.0 * (perspective.yfov() as f64 / 2.0).tan();
+            let aspect_ratio = perspective
+                .aspect_ratio()
+                .map_or(DEFAULT_ASPECT_RATIO, f64::from);
+            let mut camera = Camera::new(position, frame_height * aspect_ratio, frame_height, 1.0);
+            // Tira a escala da transformação, deixando só a rotação
+            let rotation = DMat3::from_mat4(transform);
+            camera.coord_system = DMat3::from_cols(
+                rotation.x_axis.normalize(),
+                rotation.y_axis.normalize(),
+                rotation.z_axis.normalize(),
+            );
+            self.camera = Some(camera);
+        }
+
+        for child in node.children() {
+            self.visit(child, transform)?;
+        }
+        Ok(())
+    }
+
+    /// Constrói a malha de uma primitiva, já transformada para o espaço do mundo
+    /// (None se a primitiva não é feita de triângulos ou não tem posições)
+    fn primitive_mesh(
+        &self,
+        primitive: &::gltf::Primitive,
+        transform: DMat4,
+    ) -> Result<Option<TriangleMesh>, LoadError> {
+        let reader = primitive.reader(|buffer| Some(&self.buffers[buffer.index()]));
+        let Some(positions) = reader.read_positions() else {
+            return Ok(None);
+        };
+        let vertices: Vec<DVec3> = positions
+            .map(|p| transform.transform_point3(DVec3::from_array(p.map(f64::from))))
+            .collect();
+
+        let indices: Vec<usize> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().map(|i| i as usize).collect(),
+            None => (0..vertices.len()).collect(),
+        };
+        let faces: Vec<[usize; 3]> = match primitive.mode() {
+            Mode::Triangles => indices
+                .chunks_exact(3)
+                .map(|face| [face[0], face[1], face[2]])
+                .collect(),
+            // Nas tiras, um triângulo sim e outro não tem a ordem dos vértices invertida
+            Mode::TriangleStrip => (2..indices.len())
+                .map(|i| match i % 2 {
+                    0 => [indices[i - 2], indices[i - 1], indices[i]],
+                    _ => [indices[i - 1], indices[i - 2], indices[i]],
+                })
+                .collect(),
+            Mode::TriangleFan => (2..indices.len())
+                .map(|i| [indices[0], indices[i - 1], indices[i]])
+                .collect(),
+            // Pontos e linhas não têm superfície
+            _ => return Ok(None),
+        };
+        if let Some(&index) = faces.iter().flatten().find(|&&i| i >= vertices.len()) {
+            return Err(LoadError::IndexOutOfRange {
+                index: index as i64,
+                count: vertices.len(),
+            });
+        }
+
+        let pbr = primitive.material().pbr_metallic_roughness();
+        let [r, g, b, _] = pbr.base_color_factor().map(f64::from);
+        let base_color = DVec3::new(r, g, b);
+        let material = approximate_material(
+            base_color,
+            pbr.metallic_factor() as f64,
+            pbr.roughness_factor() as f64,
+        );
+
+        let mesh = match reader.read_normals() {
+            Some(normals) => {
+                let normal_matrix = normal_matrix(transform);
+                let normals = normals
+                    .map(|n| normal_matrix * DVec3::from_array(n.map(f64::from)))
+                    .collect();
+                TriangleMesh::with_normals(vertices, normals, faces, material)
+            }
+            None => TriangleMesh::new(vertices, faces, material),
+        };
+        // A cor dos vértices multiplica a cor base do material
+        Ok(Some(match reader.read_colors(0) {
+            Some(colors) => mesh.with_colors(
+                colors
+                    .into_rgb_f32()
+                    .map(|color| DVec3::from_array(color.map(f64::from)) * base_color)
+                    .collect(),
+            ),
+            None => mesh,
+        }))
+    }
+}
+
+/// Aproxima um material PBR metallic-roughness por um material de Phong: a parte difusa
+/// é a cor base sem a parte metálica, a especular vai de 4% de branco (dielétricos) até
+/// a cor base (metais), e o expoente especular vem da rugosidade (equivalência de
+/// Blinn-Phong com a distribuição de Beckmann: e = 2 / α² - 2, com α = rugosidade²)
+fn approximate_material(base_color: DVec3, metallic: f64, roughness: f64) -> Material {
+    let alpha = (roughness * roughness).max(1e-3);
+    Material {
+        k_amb: base_color,
+        k_dif: base_color * (1.0 - metallic),
+        k_esp: DVec3::splat(0.04).lerp(base_color, metallic),
+        e: (2.0 / (alpha * alpha) - 2.0).clamp(1.0, 1000.0),
+    }
+}
+
+/// Aproxima as luzes físicas do glTF pelas luzes de Phong da cena, que não diminuem com a
+/// distância e em que 1 é a intensidade total. A iluminância (em lux) de cada luz pontual ou
+/// spot é a do centro da cena (`center`), pela lei do inverso do quadrado; a das direcionais
+/// é a própria intensidade. A intensidade de Phong é a iluminância dividida por
+/// `REFERENCE_ILLUMINANCE`, a mesma pra todos os arquivos e todas as luzes.
+fn approximate_lights(lights: Vec<PunctualLight>, center: DVec3) -> Vec<Box<dyn Light>> {
+    let illuminance = |light: &PunctualLight| match light.kind {
+        PunctualKind::Directional => light.intensity,
+        PunctualKind::Point | PunctualKind::Spot(..) => {
+            let distance = light.position.distance(center).max(MIN_LIGHT_DISTANCE);
+            light.intensity / (distance * distance)
+        }
+    };
+
+    lights
+        .iter()
+        .map(|light| -> Box<dyn Light> {
+            let intensity = illuminance(light) / REFERENCE_ILLUMINANCE;
+            match light.kind {
+                PunctualKind::Directional => {
+                    Box::new(Directional::new(light.forward, light.color, intensity))
+                }
+                PunctualKind::Point => Box::new(Point::new(light.position, light.color, intensity)),
+                PunctualKind::Spot(inner_angle, outer_angle) => Box::new(Spot::new(
+                    light.position,
+                    light.forward,
+                    inner_angle,
+                    outer_angle,
+                    light.color,
+                    intensity,
+                )),
+            }
+        })
+        .collect()
+}
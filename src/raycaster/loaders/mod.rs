@@ -0,0 +1,86 @@
+use std::{error::Error, fmt, io, path::PathBuf};
+
+mod gltf;
+mod obj;
+mod ply;
+mod stl;
+mod teapot;
+
+pub use self::gltf::{load_gltf, parse_gltf};
+pub use obj::{load_obj, parse_mtl, parse_obj};
+pub use ply::{load_ply, load_ply_points, parse_ply, parse_ply_points};
+pub use stl::{load_stl, parse_stl};
+pub use teapot::{load_teapot, parse_teapot};
+
+/// Erro ao carregar um modelo de um arquivo
+#[derive(Debug)]
+pub enum LoadError {
+    /// Erro de leitura do arquivo
+    Io(io::Error),
+    /// Erro ao ler um arquivo glTF
+    Gltf(::gltf::Error),
+    /// Linha mal formatada no arquivo (linhas começando em 1)
+    Parse { line: usize, message: String },
+    /// O arquivo acabou antes do que era esperado
+    UnexpectedEof { expected: String },
+    /// Faltam no arquivo dados necessários (como um elemento ou uma propriedade)
+    MissingData(String),
+    /// Índice de vértice (de uma face) que não existe
+    IndexOutOfRange { index: i64, count: usize },
+    /// Erro num arquivo referenciado pelo modelo (como um .mtl de um .obj)
+    InFile { path: PathBuf, error: Box<LoadError> },
+}
+
+impl LoadError {
+    /// Constrói um erro de sintaxe numa linha do arquivo
+    pub(crate) fn parse(line: usize, message: impl Into<String>) -> LoadError {
+        LoadError::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(err) => write!(f, "erro de leitura: {err}"),
+            LoadError::Gltf(err) => write!(f, "erro no glTF: {err}"),
+            LoadError::Parse { line, message } => write!(f, "linha {line}: {message}"),
+            LoadError::UnexpectedEof { expected } => {
+                write!(f, "o arquivo acabou antes do esperado: faltou {expected}")
+            }
+            LoadError::MissingData(what) => write!(f, "faltando no arquivo: {what}"),
+            LoadError::IndexOutOfRange { index, count } => {
+                write!(f, "índice de vértice {index} fora do intervalo (só há {count} vértices)")
+            }
+            LoadError::InFile { path, error } => write!(f, "em '{}': {error}", path.display()),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(err) => Some(err),
+            LoadError::Gltf(err) => Some(err),
+            LoadError::InFile { error, .. } => Some(error.as_ref()),
+            LoadError::Parse { .. }
+            | LoadError::UnexpectedEof { .. }
+            | LoadError::MissingData(_)
+            | LoadError::IndexOutOfRange { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(err: io::Error) -> LoadError {
+        LoadError::Io(err)
+    }
+}
+
+impl From<::gltf::Error> for LoadError {
+    fn from(err: ::gltf::Error) -> LoadError {
+        LoadError::Gltf(err)
+    }
+}
@@ -0,0 +1,299 @@
+use std::{collections::HashMap, fs, path::Path, str::SplitWhitespace};
+
+use glam::DVec3;
+
+use crate::{Material, loaders::LoadError, shapes::TriangleMesh};
+
+/// Carrega um modelo Wavefront .obj, junto com os arquivos .mtl referenciados
+/// nele por `mtllib` (procurados na mesma pasta do .obj).
+/// Retorna uma malha para cada par (grupo, material) do arquivo.
+pub fn load_obj(path: impl AsRef<Path>) -> Result<Vec<TriangleMesh>, LoadError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path)?;
+    let folder = path.parent().unwrap_or(Path::new(""));
+
+    let mut materials = HashMap::new();
+    for line in source.lines() {
+        let mut tokens = strip_comment(line).split_whitespace();
+        if tokens.next() == Some("mtllib") {
+            for file_name in tokens {
+                let mtl_path = folder.join(file_name);
+                let mtl_materials = fs::read_to_string(&mtl_path)
+                    .map_err(LoadError::from)
+                    .and_then(|mtl_source| parse_mtl(&mtl_source))
+                    .map_err(|error| LoadError::InFile {
+                        path: mtl_path,
+                        error: Box::new(error),
+                    })?;
+                materials.extend(mtl_materials);
+            }
+        }
+    }
+
+    parse_obj(&source, &materials)
+}
+
+/// Lê o conteúdo de um arquivo .obj. Os materiais usados em `usemtl` são procurados
+/// em `materials` (linhas `mtllib` são ignoradas aqui); os que não estão lá viram o
+/// material padrão (`Material::WHITE`).
+/// Polígonos com mais de 3 vértices são divididos em leque de triângulos.
+pub fn parse_obj(
+    source: &str,
+    materials: &HashMap<String, Material>,
+) -> Result<Vec<TriangleMesh>, LoadError> {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut tex_coord_count = 0;
+
+    // Uma malha em construção para cada par (grupo, material), na ordem em que aparecem
+    let mut meshes: Vec<MeshBuilder> = Vec::new();
+    let mut mesh_by_key: HashMap<(String, String), usize> = HashMap::new();
+    let mut group = String::new();
+    let mut material_name = String::new();
+    let mut material = Material::WHITE;
+
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i + 1;
+        let mut tokens = strip_comment(line).split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+
+        match keyword {
+            "v" => positions.push(parse_vec3(&mut tokens, line_number)?),
+            "vn" => normals.push(parse_vec3(&mut tokens, line_number)?),
+            "vt" => {
+                // Não temos texturas, mas as coordenadas são contadas pra validar os índices
+                parse_f64(tokens.next(), line_number)?;
+                tex_coord_count += 1;
+            }
+            "g" | "o" => group = tokens.collect::<Vec<_>>().join(" "),
+            "usemtl" => {
+                let name = tokens.collect::<Vec<_>>().join(" ");
+                material = materials.get(&name).copied().unwrap_or(Material::WHITE);
+                material_name = name;
+            }
+            "f" => {
+                let key = (group.clone(), material_name.clone());
+                let mesh_index = *mesh_by_key.entry(key).or_insert_with(|| {
+                    meshes.push(MeshBuilder::new(material));
+                    meshes.len() - 1
+                });
+                let mesh = &mut meshes[mesh_index];
+
+                let corners = tokens
+                    .map(|token| {
+                        let (position, normal) = parse_face_vertex(
+                            token,
+                            positions.len(),
+                            tex_coord_count,
+                            normals.len(),
+                            line_number,
+                        )?;
+                        Ok(mesh.vertex(position, normal, &positions, &normals))
+                    })
+                    .collect::<Result<Vec<_>, LoadError>>()?;
+                if corners.len() < 3 {
+                    return Err(LoadError::parse(
+                        line_number,
+                        "uma face precisa de pelo menos 3 vértices",
+                    ));
+                }
+                // Triangulação em leque: (0, 1, 2), (0, 2, 3), ...
+                for k in 1..corners.len() - 1 {
+                    mesh.faces.push([corners[0], corners[k], corners[k + 1]]);
+                }
+            }
+            // mtllib é tratado em `load_obj`; o resto (s, l, p...) não é suportado
+            _ => {}
+        }
+    }
+
+    Ok(meshes
+        .into_iter()
+        .filter(|mesh| !mesh.faces.is_empty())
+        .map(MeshBuilder::build)
+        .collect())
+}
+
+/// Lê o conteúdo de um arquivo .mtl, mapeando Ka/Kd/Ks/Ns para
+/// `k_amb`/`k_dif`/`k_esp`/`e` do material
+pub fn parse_mtl(source: &str) -> Result<HashMap<String, Material>, LoadError> {
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, Material)> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i + 1;
+        let mut tokens = strip_comment(line).split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+
+        if keyword == "newmtl" {
+            let name = tokens.collect::<Vec<_>>().join(" ");
+            if name.is_empty() {
+                return Err(LoadError::parse(line_number, "newmtl sem nome"));
+            }
+            materials.extend(current.replace((name, Material::WHITE)));
+            continue;
+        }
+
+        let Some((_, material)) = current.as_mut() else {
+            // Propriedades de material só fazem sentido depois de um newmtl
+            if matches!(keyword, "Ka" | "Kd" | "Ks" | "Ns") {
+                return Err(LoadError::parse(
+                    line_number,
+                    format!("{keyword} antes de qualquer newmtl"),
+                ));
+            }
+            continue;
+        };
+        match keyword {
+            "Ka" => material.k_amb = parse_color(&mut tokens, line_number)?,
+            "Kd" => material.k_dif = parse_color(&mut tokens, line_number)?,
+            "Ks" => material.k_esp = parse_color(&mut tokens, line_number)?,
+            "Ns" => material.e = parse_f64(tokens.next(), line_number)?,
+            // Texturas, transparência, modelo de iluminação etc. não são suportados
+            _ => {}
+        }
+    }
+    materials.extend(current);
+
+    Ok(materials)
+}
+
+/// Malha sendo montada a partir das faces de um grupo do .obj
+struct MeshBuilder {
+    material: Material,
+    vertices: Vec<DVec3>,
+    normals: Vec<Option<DVec3>>,
+    faces: Vec<[usize; 3]>,
+    /// Índice na malha de cada par (posição, normal) do arquivo já usado
+    vertex_index: HashMap<(usize, Option<usize>), usize>,
+}
+
+impl MeshBuilder {
+    fn new(material: Material) -> MeshBuilder {
+        MeshBuilder {
+            material,
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            faces: Vec::new(),
+            vertex_index: HashMap::new(),
+        }
+    }
+
+    /// Retorna o índice na malha do vértice com a posição e a normal dadas,
+    /// adicionando-o se ele ainda não foi usado
+    fn vertex(
+        &mut self,
+        position: usize,
+        normal: Option<usize>,
+        positions: &[DVec3],
+        normals: &[DVec3],
+    ) -> usize {
+        *self
+            .vertex_index
+            .entry((position, normal))
+            .or_insert_with(|| {
+                self.vertices.push(positions[position]);
+                self.normals.push(normal.map(|n| normals[n]));
+                self.vertices.len() - 1
+            })
+    }
+
+    /// Constrói a malha, calculando as normais se algum vértice não tiver uma
+    fn build(self) -> TriangleMesh {
+        match self.normals.into_iter().collect::<Option<Vec<_>>>() {
+            Some(normals) => {
+                TriangleMesh::with_normals(self.vertices, normals, self.faces, self.material)
+            }
+            None => TriangleMesh::new(self.vertices, self.faces, self.material),
+        }
+    }
+}
+
+/// Remove o comentário (tudo depois de '#') de uma linha
+fn strip_comment(line: &str) -> &str {
+    line.split('#').next().unwrap_or("")
+}
+
+fn parse_f64(token: Option<&str>, line: usize) -> Result<f64, LoadError> {
+    let token = token.ok_or_else(|| LoadError::parse(line, "faltando um número"))?;
+    token
+        .parse()
+        .map_err(|_| LoadError::parse(line, format!("número inválido '{token}'")))
+}
+
+/// Lê 3 números (valores extras, como o w de `v x y z w`, são ignorados)
+fn parse_vec3(tokens: &mut SplitWhitespace, line: usize) -> Result<DVec3, LoadError> {
+    Ok(DVec3::new(
+        parse_f64(tokens.next(), line)?,
+        parse_f64(tokens.next(), line)?,
+        parse_f64(tokens.next(), line)?,
+    ))
+}
+
+/// Lê uma cor RGB do .mtl (um único valor é repetido nos 3 canais)
+fn parse_color(tokens: &mut SplitWhitespace, line: usize) -> Result<DVec3, LoadError> {
+    let r = parse_f64(tokens.next(), line)?;
+    match tokens.next() {
+        None => Ok(DVec3::splat(r)),
+        g => Ok(DVec3::new(
+            r,
+            parse_f64(g, line)?,
+            parse_f64(tokens.next(), line)?,
+        )),
+    }
+}
+
+/// Lê um vértice de face (`v`, `v/vt`, `v//vn` ou `v/vt/vn`), retornando os índices
+/// (a partir de 0) da posição e da normal
+fn parse_face_vertex(
+    token: &str,
+    position_count: usize,
+    tex_coord_count: usize,
+    normal_count: usize,
+    line: usize,
+) -> Result<(usize, Option<usize>), LoadError> {
+    let mut parts = token.split('/');
+    let position = resolve_index(parts.next(), position_count, "vértice", line)?
+        .ok_or_else(|| LoadError::parse(line, format!("vértice de face inválido '{token}'")))?;
+    resolve_index(parts.next(), tex_coord_count, "coordenada de textura", line)?;
+    let normal = resolve_index(parts.next(), normal_count, "normal", line)?;
+    if parts.next().is_some() {
+        return Err(LoadError::parse(
+            line,
+            format!("vértice de face inválido '{token}'"),
+        ));
+    }
+    Ok((position, normal))
+}
+
+/// Converte um índice do .obj (começa em 1, negativos são relativos ao fim da lista)
+/// para um índice a partir de 0. Retorna `None` se o índice foi omitido.
+fn resolve_index(
+    token: Option<&str>,
+    count: usize,
+    kind: &str,
+    line: usize,
+) -> Result<Option<usize>, LoadError> {
+    let Some(token) = token.filter(|token| !token.is_empty()) else {
+        return Ok(None);
+    };
+    let index: i64 = token
+        .parse()
+        .map_err(|_| LoadError::parse(line, format!("índice inválido '{token}'")))?;
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        count as i64 + index
+    };
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(LoadError::parse(
+            line,
+            format!("índice de {kind} fora do intervalo: {index}"),
+        ));
+    }
+    Ok(Some(resolved as usize))
+}
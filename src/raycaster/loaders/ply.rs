@@ -0,0 +1,415 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use glam::DVec3;
+
+use crate::{
+    Material,
+    loaders::LoadError,
+    shapes::{PointCloud, TriangleMesh},
+};
+
+/// Carrega uma malha de um arquivo .ply (ASCII ou binário). Faces com mais de 3 vértices
+/// são divididas em leque de triângulos. Usa as normais (`nx`, `ny`, `nz`) dos vértices
+/// se o arquivo tiver (senão elas são calculadas), e as cores (`red`, `green`, `blue`).
+pub fn load_ply(path: impl AsRef<Path>, material: Material) -> Result<TriangleMesh, LoadError> {
+    let bytes = fs::read(path)?;
+    parse_ply(&bytes, material)
+}
+
+/// Lê o conteúdo de um arquivo .ply como uma malha (ver `load_ply`)
+pub fn parse_ply(bytes: &[u8], material: Material) -> Result<TriangleMesh, LoadError> {
+    let ply = Ply::parse(bytes)?;
+    let vertices = ply.element("vertex")?;
+    let positions = vertices.vec3(["x", "y", "z"])?;
+
+    // A lista de índices costuma se chamar "vertex_indices", mas alguns programas usam "vertex_index"
+    let face_element = ply.element("face")?;
+    let face_lists = face_element
+        .list("vertex_indices")
+        .or_else(|_| face_element.list("vertex_index"))?;
+    let mut faces = Vec::new();
+    for list in face_lists {
+        let corners = list
+            .iter()
+            .map(|&index| {
+                if index < 0.0 || index as usize >= positions.len() {
+                    return Err(LoadError::IndexOutOfRange {
+                        index: index as i64,
+                        count: positions.len(),
+                    });
+                }
+                Ok(index as usize)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Triangulação em leque: (0, 1, 2), (0, 2, 3), ...
+        for k in 1..corners.len().saturating_sub(1) {
+            faces.push([corners[0], corners[k], corners[k + 1]]);
+        }
+    }
+
+    let mesh = match vertices.vec3(["nx", "ny", "nz"]) {
+        Ok(normals) => TriangleMesh::with_normals(positions, normals, faces, material),
+        Err(_) => TriangleMesh::new(positions, faces, material),
+    };
+    Ok(match vertices.colors() {
+        Some(colors) => mesh.with_colors(colors),
+        None => mesh,
+    })
+}
+
+/// Carrega os vértices de um arquivo .ply (ASCII ou binário) como uma nuvem de pontos,
+/// com discos de raio `radius`. Usa as normais (`nx`, `ny`, `nz`) e as cores
+/// (`red`, `green`, `blue`) dos vértices, se o arquivo tiver.
+pub fn load_ply_points(
+    path: impl AsRef<Path>,
+    radius: f64,
+    material: Material,
+) -> Result<PointCloud, LoadError> {
+    let bytes = fs::read(path)?;
+    parse_ply_points(&bytes, radius, material)
+}
+
+/// Lê o conteúdo de um arquivo .ply como uma nuvem de pontos (ver `load_ply_points`)
+pub fn parse_ply_points(
+    bytes: &[u8],
+    radius: f64,
+    material: Material,
+) -> Result<PointCloud, LoadError> {
+    let ply = Ply::parse(bytes)?;
+    let vertices = ply.element("vertex")?;
+    Ok(PointCloud::new(
+        vertices.vec3(["x", "y", "z"])?,
+        vertices.vec3(["nx", "ny", "nz"]).ok(),
+        vertices.colors(),
+        radius,
+        material,
+    ))
+}
+
+/// Conteúdo de um arquivo .ply: os elementos declarados no cabeçalho, com os valores lidos
+pub(crate) struct Ply {
+    pub elements: Vec<Element>,
+}
+
+/// Elemento de um arquivo .ply (como `vertex` ou `face`) com os valores de cada propriedade
+pub(crate) struct Element {
+    pub name: String,
+    pub count: usize,
+    properties: Vec<Property>,
+    /// Valores das propriedades escalares, um por linha do elemento
+    scalars: HashMap<String, Vec<f64>>,
+    /// Valores das propriedades de lista, uma lista por linha do elemento
+    lists: HashMap<String, Vec<Vec<f64>>>,
+}
+
+/// Propriedade declarada no cabeçalho de um elemento
+struct Property {
+    name: String,
+    kind: ScalarType,
+    /// Tipo do tamanho da lista, se a propriedade é uma lista
+    list_count: Option<ScalarType>,
+}
+
+/// Tipos numéricos do formato .ply
+#[derive(Clone, Copy, PartialEq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+/// Formato do corpo do arquivo
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+impl Ply {
+    /// Lê um arquivo .ply inteiro: o cabeçalho e depois os valores de cada elemento
+    pub fn parse(bytes: &[u8]) -> Result<Ply, LoadError> {
+        // O cabeçalho é texto, e termina na linha "end_header"
+        let header_end = bytes
+            .windows(b"end_header".len())
+            .position(|window| window == b"end_header")
+            .ok_or(LoadError::UnexpectedEof {
+                expected: "fim do cabeçalho (end_header)".into(),
+            })?;
+        let body_start = bytes[header_end..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(bytes.len(), |i| header_end + i + 1);
+        let header = String::from_utf8_lossy(&bytes[..header_end]);
+
+        let mut lines = header.lines().enumerate().map(|(i, line)| (i + 1, line));
+        match lines.next() {
+            Some((_, line)) if line.trim() == "ply" => {}
+            _ => return Err(LoadError::parse(1, "o arquivo não começa com 'ply'")),
+        }
+
+        let mut format = None;
+        let mut elements: Vec<Element> = Vec::new();
+        for (line_number, line) in lines {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens[..] {
+                [] | ["comment", ..] | ["obj_info", ..] => {}
+                ["format", name, _version] => {
+                    format = Some(match name {
+                        "ascii" => Format::Ascii,
+                        "binary_little_endian" => Format::BinaryLittleEndian,
+                        "binary_big_endian" => Format::BinaryBigEndian,
+                        _ => {
+                            return Err(LoadError::parse(
+                                line_number,
+                                format!("formato desconhecido '{name}'"),
+                            ));
+                        }
+                    })
+                }
+                ["element", name, count] => elements.push(Element {
+                    name: name.to_string(),
+                    count: count.parse().map_err(|_| {
+                        LoadError::parse(line_number, format!("quantidade inválida '{count}'"))
+                    })?,
+                    properties: Vec::new(),
+                    scalars: HashMap::new(),
+                    lists: HashMap::new(),
+                }),
+                ["property", ..] => {
+                    let element = elements.last_mut().ok_or_else(|| {
+                        LoadError::parse(line_number, "propriedade fora de um elemento")
+                    })?;
+                    element
+                        .properties
+                        .push(Property::parse(&tokens[1..], line_number)?);
+                }
+                _ => {
+                    return Err(LoadError::parse(
+                        line_number,
+                        format!("linha de cabeçalho inválida '{line}'"),
+                    ));
+                }
+            }
+        }
+        let format = format.ok_or_else(|| LoadError::parse(1, "o cabeçalho não tem 'format'"))?;
+
+        let body = &bytes[body_start..];
+        match format {
+            Format::Ascii => {
+                // As linhas do corpo são numeradas depois das linhas do cabeçalho
+                let first_line = header.lines().count() + 2;
+                read_ascii(&mut elements, &String::from_utf8_lossy(body), first_line)?
+            }
+            _ => read_binary(&mut elements, body, format == Format::BinaryBigEndian)?,
+        }
+        Ok(Ply { elements })
+    }
+
+    /// Retorna o elemento com o nome dado
+    pub fn element(&self, name: &str) -> Result<&Element, LoadError> {
+        self.elements
+            .iter()
+            .find(|element| element.name == name)
+            .ok_or_else(|| LoadError::MissingData(format!("elemento '{name}'")))
+    }
+}
+
+impl Element {
+    /// Valores de uma propriedade escalar do elemento
+    pub fn scalar(&self, name: &str) -> Result<&[f64], LoadError> {
+        self.scalars
+            .get(name)
+            .map(Vec::as_slice)
+            .ok_or_else(|| LoadError::MissingData(format!("propriedade '{}.{name}'", self.name)))
+    }
+
+    /// Valores de uma propriedade de lista do elemento
+    pub fn list(&self, name: &str) -> Result<&[Vec<f64>], LoadError> {
+        self.lists
+            .get(name)
+            .map(Vec::as_slice)
+            .ok_or_else(|| LoadError::MissingData(format!("propriedade '{}.{name}'", self.name)))
+    }
+
+    /// Junta 3 propriedades escalares do elemento em vetores
+    pub fn vec3(&self, [x, y, z]: [&str; 3]) -> Result<Vec<DVec3>, LoadError> {
+        let (x, y, z) = (self.scalar(x)?, self.scalar(y)?, self.scalar(z)?);
+        Ok((0..self.count)
+            .map(|i| DVec3::new(x[i], y[i], z[i]))
+            .collect())
+    }
+
+    /// Cores (`red`, `green`, `blue`) do elemento, de 0 a 1. Cores inteiras são
+    /// divididas pelo maior valor do tipo delas (255, para `uchar`).
+    pub fn colors(&self) -> Option<Vec<DVec3>> {
+        let colors = self.vec3(["red", "green", "blue"]).ok()?;
+        let kind = self
+            .properties
+            .iter()
+            .find(|property| property.name == "red")?
+            .kind;
+        let scale = match kind {
+            ScalarType::U8 | ScalarType::I8 => 1.0 / 255.0,
+            ScalarType::U16 | ScalarType::I16 => 1.0 / 65535.0,
+            ScalarType::U32 | ScalarType::I32 => 1.0 / u32::MAX as f64,
+            ScalarType::F32 | ScalarType::F64 => 1.0,
+        };
+        Some(colors.into_iter().map(|color| color * scale).collect())
+    }
+
+    /// Lê e guarda os valores de uma linha do elemento
+    fn push_row(
+        &mut self,
+        mut value: impl FnMut(ScalarType) -> Result<f64, LoadError>,
+    ) -> Result<(), LoadError> {
+        for property in &self.properties {
+            match property.list_count {
+                None => {
+                    let v = value(property.kind)?;
+                    self.scalars
+                        .entry(property.name.clone())
+                        .or_default()
+                        .push(v);
+                }
+                Some(count_kind) => {
+                    let count = value(count_kind)? as usize;
+                    let list = (0..count)
+                        .map(|_| value(property.kind))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    self.lists
+                        .entry(property.name.clone())
+                        .or_default()
+                        .push(list);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Property {
+    /// Lê a declaração de uma propriedade (sem a palavra `property`):
+    /// `<tipo> <nome>` ou `list <tipo do tamanho> <tipo> <nome>`
+    fn parse(tokens: &[&str], line_number: usize) -> Result<Property, LoadError> {
+        let kind = |name: &str| {
+            ScalarType::parse(name)
+                .ok_or_else(|| LoadError::parse(line_number, format!("tipo desconhecido '{name}'")))
+        };
+        match tokens {
+            ["list", count_kind, item_kind, name] => Ok(Property {
+                name: name.to_string(),
+                kind: kind(item_kind)?,
+                list_count: Some(kind(count_kind)?),
+            }),
+            [item_kind, name] => Ok(Property {
+                name: name.to_string(),
+                kind: kind(item_kind)?,
+                list_count: None,
+            }),
+            _ => Err(LoadError::parse(line_number, "propriedade inválida")),
+        }
+    }
+}
+
+impl ScalarType {
+    /// Lê o nome de um tipo (nos dois estilos: `uchar` ou `uint8`)
+    fn parse(name: &str) -> Option<ScalarType> {
+        Some(match name {
+            "char" | "int8" => ScalarType::I8,
+            "uchar" | "uint8" => ScalarType::U8,
+            "short" | "int16" => ScalarType::I16,
+            "ushort" | "uint16" => ScalarType::U16,
+            "int" | "int32" => ScalarType::I32,
+            "uint" | "uint32" => ScalarType::U32,
+            "float" | "float32" => ScalarType::F32,
+            "double" | "float64" => ScalarType::F64,
+            _ => return None,
+        })
+    }
+
+    /// Tamanho do tipo em bytes
+    fn size(self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::F64 => 8,
+        }
+    }
+
+    /// Converte os bytes de um valor desse tipo
+    fn decode(self, bytes: &[u8], big_endian: bool) -> f64 {
+        macro_rules! decode {
+            ($type:ty) => {{
+                let bytes = bytes.try_into().unwrap();
+                if big_endian {
+                    <$type>::from_be_bytes(bytes) as f64
+                } else {
+                    <$type>::from_le_bytes(bytes) as f64
+                }
+            }};
+        }
+        match self {
+            ScalarType::I8 => decode!(i8),
+            ScalarType::U8 => decode!(u8),
+            ScalarType::I16 => decode!(i16),
+            ScalarType::U16 => decode!(u16),
+            ScalarType::I32 => decode!(i32),
+            ScalarType::U32 => decode!(u32),
+            ScalarType::F32 => decode!(f32),
+            ScalarType::F64 => decode!(f64),
+        }
+    }
+}
+
+/// Lê o corpo ASCII do arquivo: uma linha por linha de elemento, na ordem do cabeçalho
+fn read_ascii(elements: &mut [Element], body: &str, first_line: usize) -> Result<(), LoadError> {
+    let mut lines = body
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + first_line, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+    for element in elements {
+        for _ in 0..element.count {
+            let (line_number, line) = lines.next().ok_or_else(|| LoadError::UnexpectedEof {
+                expected: format!("elemento '{}'", element.name),
+            })?;
+            let mut tokens = line.split_whitespace();
+            element.push_row(|_| {
+                let token = tokens
+                    .next()
+                    .ok_or_else(|| LoadError::parse(line_number, "faltam valores na linha"))?;
+                token.parse().map_err(|_| {
+                    LoadError::parse(line_number, format!("número inválido '{token}'"))
+                })
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Lê o corpo binário do arquivo: os valores de cada linha de elemento em sequência
+fn read_binary(elements: &mut [Element], body: &[u8], big_endian: bool) -> Result<(), LoadError> {
+    let mut offset = 0;
+    for element in elements {
+        let name = element.name.clone();
+        for _ in 0..element.count {
+            element.push_row(|kind| {
+                let bytes = body.get(offset..offset + kind.size()).ok_or_else(|| {
+                    LoadError::UnexpectedEof {
+                        expected: format!("elemento '{name}'"),
+                    }
+                })?;
+                offset += kind.size();
+                Ok(kind.decode(bytes, big_endian))
+            })?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::{fs, path::Path};
+
+use glam::DVec3;
+
+use crate::{Material, loaders::LoadError, shapes::TriangleMesh};
+
+/// Tamanho do cabeçalho de um .stl binário (80 bytes de texto + número de triângulos)
+const BINARY_HEADER_SIZE: usize = 84;
+/// Tamanho de cada triângulo num .stl binário (normal, 3 vértices e 2 bytes de atributos)
+const BINARY_TRIANGLE_SIZE: usize = 50;
+
+/// Carrega uma malha de um arquivo .stl (ASCII ou binário)
+pub fn load_stl(path: impl AsRef<Path>, material: Material) -> Result<TriangleMesh, LoadError> {
+    let bytes = fs::read(path)?;
+    parse_stl(&bytes, material)
+}
+
+/// Lê o conteúdo de um arquivo .stl, ASCII ou binário (o formato é detectado pelo
+/// conteúdo). Os triângulos do .stl não compartilham vértices, então a malha tem
+/// sombreamento plano, com a normal de cada face nos seus 3 vértices. Faces sem normal
+/// (ou com normal nula) usam a normal calculada pela ordem dos vértices.
+pub fn parse_stl(bytes: &[u8], material: Material) -> Result<TriangleMesh, LoadError> {
+    // Arquivos binários também podem começar com "solid", então o tamanho do arquivo
+    // (que é exato no formato binário) decide primeiro, e o texto tem que ser válido
+    let triangles = match std::str::from_utf8(bytes) {
+        Ok(source) if !is_binary(bytes) && source.trim_start().starts_with("solid") => {
+            parse_ascii(source)?
+        }
+        _ => parse_binary(bytes)?,
+    };
+
+    let mut vertices = Vec::with_capacity(triangles.len() * 3);
+    let mut normals = Vec::with_capacity(triangles.len() * 3);
+    let mut faces = Vec::with_capacity(triangles.len());
+    for (normal, [a, b, c]) in triangles {
+        let normal = normal
+            .try_normalize()
+            .or_else(|| (b - a).cross(c - a).try_normalize())
+            .unwrap_or(DVec3::Y);
+        let first = vertices.len();
+        vertices.extend([a, b, c]);
+        normals.extend([normal; 3]);
+        faces.push([first, first + 1, first + 2]);
+    }
+    Ok(TriangleMesh::with_normals(
+        vertices, normals, faces, material,
+    ))
+}
+
+/// Diz se o arquivo tem exatamente o tamanho de um .stl binário com o número
+/// de triângulos do cabeçalho
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes.len() >= BINARY_HEADER_SIZE
+        && BINARY_HEADER_SIZE + triangle_count(bytes) * BINARY_TRIANGLE_SIZE == bytes.len()
+}
+
+/// Número de triângulos no cabeçalho de um .stl binário
+fn triangle_count(bytes: &[u8]) -> usize {
+    u32::from_le_bytes(bytes[80..84].try_into().unwrap()) as usize
+}
+
+/// Lê os triângulos (normal e vértices) de um .stl binário
+fn parse_binary(bytes: &[u8]) -> Result<Vec<(DVec3, [DVec3; 3])>, LoadError> {
+    if bytes.len() < BINARY_HEADER_SIZE {
+        return Err(LoadError::UnexpectedEof {
+            expected: "cabeçalho do .stl binário".into(),
+        });
+    }
+    let count = triangle_count(bytes);
+    let body = &bytes[BINARY_HEADER_SIZE..];
+    if body.len() < count * BINARY_TRIANGLE_SIZE {
+        return Err(LoadError::UnexpectedEof {
+            expected: format!("{count} triângulos"),
+        });
+    }
+
+    let vec3 = |bytes: &[u8]| {
+        let [x, y, z] =
+            [0, 4, 8].map(|i| f32::from_le_bytes(bytes[i..i + 4].try_into().unwrap()) as f64);
+        DVec3::new(x, y, z)
+    };
+    Ok(body
+        .chunks_exact(BINARY_TRIANGLE_SIZE)
+        .take(count)
+        .map(|triangle| {
+            (
+                vec3(&triangle[0..12]),
+                [
+                    vec3(&triangle[12..24]),
+                    vec3(&triangle[24..36]),
+                    vec3(&triangle[36..48]),
+                ],
+            )
+        })
+        .collect())
+}
+
+/// Lê os triângulos (normal e vértices) de um .stl ASCII:
+/// `facet normal nx ny nz`, `outer loop`, 3 linhas `vertex x y z`, `endloop` e `endfacet`.
+/// Facetas com mais de 3 vértices são divididas em leque de triângulos.
+fn parse_ascii(source: &str) -> Result<Vec<(DVec3, [DVec3; 3])>, LoadError> {
+    let mut triangles = Vec::new();
+    // Normal e vértices da faceta atual, se estamos dentro de uma
+    let mut facet: Option<(DVec3, Vec<DVec3>)> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        let line_number = i + 1;
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+
+        match keyword {
+            "facet" => {
+                if facet.is_some() {
+                    return Err(LoadError::parse(line_number, "facet dentro de outra facet"));
+                }
+                let normal = match tokens.next() {
+                    Some("normal") => parse_vec3(&mut tokens, line_number)?,
+                    _ => DVec3::ZERO,
+                };
+                facet = Some((normal, Vec::new()));
+            }
+            "vertex" => {
+                let (_, vertices) = facet
+                    .as_mut()
+                    .ok_or_else(|| LoadError::parse(line_number, "vertex fora de uma facet"))?;
+                vertices.push(parse_vec3(&mut tokens, line_number)?);
+            }
+            "endfacet" => {
+                let (normal, vertices) = facet
+                    .take()
+                    .ok_or_else(|| LoadError::parse(line_number, "endfacet sem facet"))?;
+                if vertices.len() < 3 {
+                    return Err(LoadError::parse(
+                        line_number,
+                        "uma facet precisa de pelo menos 3 vértices",
+                    ));
+                }
+                // Triangulação em leque: (0, 1, 2), (0, 2, 3), ...
+                for k in 1..vertices.len() - 1 {
+                    triangles.push((normal, [vertices[0], vertices[k], vertices[k + 1]]));
+                }
+            }
+            "solid" | "endsolid" | "outer" | "endloop" => {}
+            _ => {
+                return Err(LoadError::parse(
+                    line_number,
+                    format!("palavra desconhecida '{keyword}'"),
+                ));
+            }
+        }
+    }
+    if facet.is_some() {
+        return Err(LoadError::UnexpectedEof {
+            expected: "endfacet".into(),
+        });
+    }
+    Ok(triangles)
+}
+
+/// Lê 3 números de uma linha
+fn parse_vec3<'a>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+    line: usize,
+) -> Result<DVec3, LoadError> {
+    let mut next = || {
+        let token = tokens
+            .next()
+            .ok_or_else(|| LoadError::parse(line, "faltando um número"))?;
+        token
+            .parse()
+            .map_err(|_| LoadError::parse(line, format!("número inválido '{token}'")))
+    };
+    Ok(DVec3::new(next()?, next()?, next()?))
+}
@@ -0,0 +1,105 @@
+use std::{fs, path::Path};
+
+use glam::DVec3;
+
+use crate::{Material, loaders::LoadError, shapes::BezierPatch};
+
+/// Carrega um arquivo de patches no formato do bule de Newell (Utah teapot)
+pub fn load_teapot(
+    path: impl AsRef<Path>,
+    material: Material,
+) -> Result<Vec<BezierPatch>, LoadError> {
+    let source = fs::read_to_string(path)?;
+    parse_teapot(&source, material)
+}
+
+/// Lê patches de Bézier bicúbicos no formato do bule de Newell:
+/// uma linha com o número de patches, uma linha por patch com os 16 índices
+/// (começando em 1) dos seus pontos de controle, uma linha com o número de pontos
+/// e uma linha por ponto com as coordenadas x, y e z. Os números podem ser separados
+/// por vírgulas e/ou espaços. O bule original tem o eixo z para cima.
+pub fn parse_teapot(source: &str, material: Material) -> Result<Vec<BezierPatch>, LoadError> {
+    // Linhas com conteúdo, com os números de linha do arquivo (começando em 1)
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    let patch_count = parse_count(lines.next(), "número de patches")?;
+    let mut patches = Vec::with_capacity(patch_count);
+    for _ in 0..patch_count {
+        let (line_number, line) = lines.next().ok_or_else(|| eof("patch"))?;
+        let indices = tokens(line)
+            .map(|token| {
+                token
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&index| index > 0)
+                    .ok_or_else(|| {
+                        LoadError::parse(line_number, format!("índice inválido '{token}'"))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let indices: [usize; 16] = indices.try_into().map_err(|indices: Vec<_>| {
+            LoadError::parse(
+                line_number,
+                format!("o patch tem {} índices, em vez de 16", indices.len()),
+            )
+        })?;
+        patches.push((line_number, indices));
+    }
+
+    let point_count = parse_count(lines.next(), "número de pontos")?;
+    let mut points = Vec::with_capacity(point_count);
+    for _ in 0..point_count {
+        let (line_number, line) = lines.next().ok_or_else(|| eof("ponto de controle"))?;
+        let coordinates = tokens(line)
+            .map(|token| {
+                token.parse::<f64>().map_err(|_| {
+                    LoadError::parse(line_number, format!("número inválido '{token}'"))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let [x, y, z] = coordinates[..] else {
+            return Err(LoadError::parse(line_number, "esperava 3 coordenadas"));
+        };
+        points.push(DVec3::new(x, y, z));
+    }
+
+    patches
+        .into_iter()
+        .map(|(line_number, indices)| {
+            let mut control_points = [[DVec3::ZERO; 4]; 4];
+            for (i, index) in indices.into_iter().enumerate() {
+                control_points[i / 4][i % 4] = *points.get(index - 1).ok_or_else(|| {
+                    LoadError::parse(
+                        line_number,
+                        format!("índice {index} fora da lista de pontos"),
+                    )
+                })?;
+            }
+            Ok(BezierPatch::new(control_points, material))
+        })
+        .collect()
+}
+
+/// Separa os números de uma linha, que podem estar separados por vírgulas e/ou espaços
+fn tokens(line: &str) -> impl Iterator<Item = &str> {
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+}
+
+/// Lê uma linha com uma quantidade (de patches ou de pontos)
+fn parse_count(line: Option<(usize, &str)>, what: &str) -> Result<usize, LoadError> {
+    let (line_number, line) = line.ok_or_else(|| eof(what))?;
+    line.parse()
+        .map_err(|_| LoadError::parse(line_number, format!("{what} inválido '{line}'")))
+}
+
+/// Erro de arquivo que acabou antes do esperado
+fn eof(what: &str) -> LoadError {
+    LoadError::UnexpectedEof {
+        expected: what.to_string(),
+    }
+}
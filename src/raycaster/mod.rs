@@ -1,15 +1,28 @@
 pub mod shapes;
 pub mod lights;
+pub mod loaders;
+pub mod sdf;
+pub mod solvers;
 pub mod transforms;
 
+mod aabb;
+mod bvh;
 mod camera;
 mod intersection;
 mod material;
+mod packet;
 mod ray;
+mod resolution;
 mod scene;
+mod tiles;
 
+pub use aabb::Aabb;
+pub use bvh::Bvh;
 pub use camera::Camera;
 pub use intersection::Intersection;
 pub use material::Material;
+pub use packet::{LaneMask, Lanes, Lanes3, PACKET_SIZE, RayPacket};
 pub use ray::Ray;
+pub use resolution::DynamicResolution;
 pub use scene::Scene;
+pub use tiles::{CancellationToken, Tile, TileOrder, TileProgress, TileScheduler};
@@ -0,0 +1,296 @@
+use std::{
+    array,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use glam::DVec3;
+
+use crate::Ray;
+
+/// Número de raios de um `RayPacket`
+pub const PACKET_SIZE: usize = 4;
+
+/// Um valor por raio de um pacote. As operações são feitas raio a raio em laços de
+/// tamanho fixo, que o compilador transforma em instruções SIMD.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lanes(pub [f64; PACKET_SIZE]);
+
+/// Um booleano por raio de um pacote
+pub type LaneMask = [bool; PACKET_SIZE];
+
+/// Um vetor por raio de um pacote, guardado por coordenada (o x de todos os raios,
+/// depois o y, depois o z), que é o formato em que as contas viram SIMD
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lanes3 {
+    pub x: Lanes,
+    pub y: Lanes,
+    pub z: Lanes,
+}
+
+/// Pacote de raios testados juntos. Raios coerentes (com origens e direções parecidas,
+/// como os raios primários de pixels vizinhos) atravessam quase as mesmas caixas da BVH e
+/// acertam quase os mesmos objetos, então testar o pacote de uma vez custa pouco mais
+/// que testar um raio só. Cada raio tem o seu intervalo `t_min < t < t_max`; as posições
+/// sem raio (em pacotes incompletos) têm intervalo vazio e nunca colidem.
+#[derive(Clone, Debug)]
+pub struct RayPacket {
+    /// Pontos 0 dos raios
+    pub p0: Lanes3,
+    /// Direções dos raios
+    pub dr: Lanes3,
+    /// Inícios dos intervalos válidos dos raios
+    pub t_min: Lanes,
+    /// Fins dos intervalos válidos dos raios
+    pub t_max: Lanes,
+}
+
+impl Lanes {
+    /// Valor infinito em todos os raios (o t dos raios sem interseção)
+    pub const INFINITY: Lanes = Lanes([f64::INFINITY; PACKET_SIZE]);
+
+    /// O mesmo valor em todos os raios
+    pub fn splat(value: f64) -> Lanes {
+        Lanes([value; PACKET_SIZE])
+    }
+
+    /// Aplica f ao valor de cada raio
+    pub fn map(self, f: impl Fn(f64) -> f64) -> Lanes {
+        Lanes(self.0.map(f))
+    }
+
+    /// Aplica f aos valores de cada raio nos dois operandos
+    pub fn zip(self, other: Lanes, f: impl Fn(f64, f64) -> f64) -> Lanes {
+        Lanes(array::from_fn(|lane| f(self.0[lane], other.0[lane])))
+    }
+
+    /// Compara os valores de cada raio nos dois operandos
+    pub fn compare(self, other: Lanes, f: impl Fn(f64, f64) -> bool) -> LaneMask {
+        array::from_fn(|lane| f(self.0[lane], other.0[lane]))
+    }
+
+    /// Em quais raios o valor é menor que `value`
+    pub fn lt(self, value: f64) -> LaneMask {
+        self.compare(Lanes::splat(value), |a, b| a < b)
+    }
+
+    /// Em quais raios o valor é maior ou igual a `value`
+    pub fn ge(self, value: f64) -> LaneMask {
+        self.compare(Lanes::splat(value), |a, b| a >= b)
+    }
+
+    /// Em quais raios o valor está no intervalo fechado `[min, max]`
+    pub fn within(self, min: f64, max: f64) -> LaneMask {
+        self.0.map(|a| min <= a && a <= max)
+    }
+
+    /// Em cada raio, o valor de `a` se a máscara é verdadeira, e o de `b` se não
+    pub fn select(mask: LaneMask, a: Lanes, b: Lanes) -> Lanes {
+        Lanes(array::from_fn(|lane| {
+            if mask[lane] { a.0[lane] } else { b.0[lane] }
+        }))
+    }
+
+    /// Raiz quadrada em cada raio
+    pub fn sqrt(self) -> Lanes {
+        self.map(f64::sqrt)
+    }
+
+    /// Mínimo em cada raio
+    pub fn min(self, other: Lanes) -> Lanes {
+        self.zip(other, f64::min)
+    }
+
+    /// Máximo em cada raio
+    pub fn max(self, other: Lanes) -> Lanes {
+        self.zip(other, f64::max)
+    }
+
+    /// Menor valor entre todos os raios
+    pub fn min_element(self) -> f64 {
+        self.0.into_iter().fold(f64::INFINITY, f64::min)
+    }
+
+    /// Maior valor entre todos os raios
+    pub fn max_element(self) -> f64 {
+        self.0.into_iter().fold(f64::NEG_INFINITY, f64::max)
+    }
+}
+
+/// Operações aritméticas raio a raio, entre dois `Lanes` ou entre um `Lanes` e um f64
+macro_rules! lanes_operator {
+    ($trait:ident, $method:ident, $operator:tt) => {
+        impl $trait for Lanes {
+            type Output = Lanes;
+            fn $method(self, other: Lanes) -> Lanes {
+                self.zip(other, |a, b| a $operator b)
+            }
+        }
+
+        impl $trait<f64> for Lanes {
+            type Output = Lanes;
+            fn $method(self, other: f64) -> Lanes {
+                self.map(|a| a $operator other)
+            }
+        }
+    };
+}
+
+lanes_operator!(Add, add, +);
+lanes_operator!(Sub, sub, -);
+lanes_operator!(Mul, mul, *);
+lanes_operator!(Div, div, /);
+
+impl Neg for Lanes {
+    type Output = Lanes;
+    fn neg(self) -> Lanes {
+        self.map(|a| -a)
+    }
+}
+
+impl Lanes3 {
+    /// Construtor a partir das coordenadas
+    pub fn new(x: Lanes, y: Lanes, z: Lanes) -> Lanes3 {
+        Lanes3 { x, y, z }
+    }
+
+    /// Construtor a partir do vetor de cada raio
+    pub fn from_vectors(vectors: [DVec3; PACKET_SIZE]) -> Lanes3 {
+        Lanes3::new(
+            Lanes(vectors.map(|v| v.x)),
+            Lanes(vectors.map(|v| v.y)),
+            Lanes(vectors.map(|v| v.z)),
+        )
+    }
+
+    /// O mesmo vetor em todos os raios
+    pub fn splat(v: DVec3) -> Lanes3 {
+        Lanes3::new(Lanes::splat(v.x), Lanes::splat(v.y), Lanes::splat(v.z))
+    }
+
+    /// Vetor de um dos raios
+    pub fn lane(&self, lane: usize) -> DVec3 {
+        DVec3::new(self.x.0[lane], self.y.0[lane], self.z.0[lane])
+    }
+
+    /// Coordenadas x, y e z
+    pub fn to_array(self) -> [Lanes; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    /// Produto escalar em cada raio
+    pub fn dot(self, other: Lanes3) -> Lanes {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Quadrado do tamanho em cada raio
+    pub fn length_squared(self) -> Lanes {
+        self.dot(self)
+    }
+
+    /// Projeção de cada vetor na direção unitária n
+    pub fn project_onto_normalized(self, n: DVec3) -> Lanes3 {
+        Lanes3::splat(n) * self.dot(Lanes3::splat(n))
+    }
+
+    /// Componente de cada vetor perpendicular à direção unitária n
+    pub fn reject_from_normalized(self, n: DVec3) -> Lanes3 {
+        self - self.project_onto_normalized(n)
+    }
+}
+
+impl Add for Lanes3 {
+    type Output = Lanes3;
+    fn add(self, other: Lanes3) -> Lanes3 {
+        Lanes3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Lanes3 {
+    type Output = Lanes3;
+    fn sub(self, other: Lanes3) -> Lanes3 {
+        Lanes3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<Lanes> for Lanes3 {
+    type Output = Lanes3;
+    fn mul(self, other: Lanes) -> Lanes3 {
+        Lanes3::new(self.x * other, self.y * other, self.z * other)
+    }
+}
+
+impl RayPacket {
+    /// Construtor do pacote a partir de 1 a `PACKET_SIZE` raios (as posições que sobram
+    /// ficam vazias)
+    pub fn new(rays: &[Ray]) -> RayPacket {
+        assert!(
+            (1..=PACKET_SIZE).contains(&rays.len()),
+            "um pacote tem de 1 a {PACKET_SIZE} raios"
+        );
+        // As posições vazias repetem o primeiro raio, pra não gerar contas com NaN
+        let ray = |lane: usize| rays.get(lane).unwrap_or(&rays[0]);
+        RayPacket {
+            p0: Lanes3::from_vectors(array::from_fn(|lane| ray(lane).p0)),
+            dr: Lanes3::from_vectors(array::from_fn(|lane| ray(lane).dr)),
+            t_min: Lanes(array::from_fn(|lane| {
+                rays.get(lane).map_or(f64::INFINITY, |ray| ray.t_min)
+            })),
+            t_max: Lanes(array::from_fn(|lane| {
+                rays.get(lane).map_or(f64::NEG_INFINITY, |ray| ray.t_max)
+            })),
+        }
+    }
+
+    /// Diz se a posição tem um raio com intervalo não vazio
+    pub fn is_active(&self, lane: usize) -> bool {
+        self.t_min.0[lane] < self.t_max.0[lane]
+    }
+
+    /// Raio de uma das posições do pacote
+    pub fn ray(&self, lane: usize) -> Ray {
+        Ray::new(self.p0.lane(lane), self.dr.lane(lane))
+            .with_interval(self.t_min.0[lane], self.t_max.0[lane])
+    }
+
+    /// Retorna os pontos t dos raios (p0 + dr*t)
+    pub fn at(&self, t: Lanes) -> Lanes3 {
+        self.p0 + self.dr * t
+    }
+
+    /// Testa, em cada raio, se t está no intervalo válido dele
+    pub fn contains(&self, t: Lanes) -> LaneMask {
+        array::from_fn(|lane| self.t_min.0[lane] < t.0[lane] && t.0[lane] < self.t_max.0[lane])
+    }
+
+    /// Em cada raio, t se ele está no intervalo do raio e a máscara é verdadeira,
+    /// e infinito (sem interseção) se não
+    pub fn clip(&self, t: Lanes, valid: LaneMask) -> Lanes {
+        let inside = self.contains(t);
+        Lanes::select(
+            array::from_fn(|lane| valid[lane] && inside[lane]),
+            t,
+            Lanes::INFINITY,
+        )
+    }
+
+    /// Termina o intervalo dos raios em que t está dentro dele em t (pra que só interseções
+    /// mais próximas que t sejam aceitas daqui pra frente). Retorna quais raios mudaram.
+    pub fn shrink(&mut self, t: Lanes) -> LaneMask {
+        let inside = self.contains(t);
+        self.t_max = Lanes::select(inside, t, self.t_max);
+        inside
+    }
+
+    /// Diz se os raios ativos do pacote vão pro mesmo octante (direções com os mesmos
+    /// sinais em x, y e z). Pacotes assim visitam as caixas da BVH na mesma ordem; os
+    /// outros são melhor testados raio a raio.
+    pub fn is_coherent(&self) -> bool {
+        let mut octants = (0..PACKET_SIZE)
+            .filter(|&lane| self.is_active(lane))
+            .map(|lane| self.dr.lane(lane).cmplt(DVec3::ZERO));
+        let Some(first) = octants.next() else {
+            return true;
+        };
+        octants.all(|octant| octant == first)
+    }
+}
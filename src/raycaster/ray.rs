@@ -1,17 +1,59 @@
 use glam::DVec3;
 
-/// Raio definido pela função p = p0 + dr*t
+/// Afastamento relativo da origem dos raios secundários (ver `Ray::from_surface`)
+const ORIGIN_OFFSET: f64 = 1e-6;
+
+/// Raio definido pela função p = p0 + dr*t, válido só no intervalo `t_min < t < t_max`
 pub struct Ray {
     /// Ponto 0 do raio
     pub p0: DVec3,
     /// Direção do raio
     pub dr: DVec3,
+    /// Início do intervalo válido do raio (as interseções têm t > t_min)
+    pub t_min: f64,
+    /// Fim do intervalo válido do raio (as interseções têm t < t_max)
+    pub t_max: f64,
 }
 
 impl Ray {
+    /// Construtor do raio, válido para todo t positivo
     #[inline]
     pub fn new(p0: DVec3, dr: DVec3) -> Ray {
-        Ray { p0, dr }
+        Ray {
+            p0,
+            dr,
+            t_min: 0.0,
+            t_max: f64::INFINITY,
+        }
+    }
+
+    /// Raio secundário (de sombra, reflexão, etc.) que sai de um ponto de uma superfície.
+    /// A origem é afastada do ponto ao longo da normal geométrica, pro lado em que o raio
+    /// vai, numa distância proporcional ao tamanho das coordenadas do ponto (que é o
+    /// tamanho do erro de arredondamento delas). Assim o raio não colide com a própria
+    /// superfície de onde sai, sem precisar de um t mínimo em cada forma.
+    pub fn from_surface(p: DVec3, geometric_normal: DVec3, dr: DVec3) -> Ray {
+        let offset = geometric_normal * ORIGIN_OFFSET * p.abs().max_element().max(1.0);
+        let p0 = if geometric_normal.dot(dr) < 0.0 {
+            p - offset
+        } else {
+            p + offset
+        };
+        Ray::new(p0, dr)
+    }
+
+    /// Retorna o raio com o intervalo válido trocado por `t_min < t < t_max`
+    pub fn with_interval(self, t_min: f64, t_max: f64) -> Ray {
+        Ray {
+            t_min,
+            t_max,
+            ..self
+        }
+    }
+
+    /// Testa se t está no intervalo válido do raio
+    pub fn contains(&self, t: f64) -> bool {
+        self.t_min < t && t < self.t_max
     }
 
     /// Retorna ponto t do raio (p0 + dr*t)
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+/// Controle de resolução dinâmica: escolhe a fração da resolução da janela em que os
+/// frames são renderizados, pra que cada frame leve no máximo `frame_budget`. A fração é
+/// ajustada a cada frame pelo tempo que o anterior levou.
+#[derive(Clone, Copy, Debug)]
+pub struct DynamicResolution {
+    /// Tempo desejado pra renderizar um frame
+    pub frame_budget: Duration,
+    /// Menor fração da resolução que pode ser usada
+    pub min_scale: f64,
+    /// Fração atual da resolução (em cada eixo)
+    scale: f64,
+}
+
+impl DynamicResolution {
+    /// Construtor do controle, começando na resolução cheia e com fração mínima 1/8
+    pub fn new(frame_budget: Duration) -> DynamicResolution {
+        DynamicResolution {
+            frame_budget,
+            min_scale: 0.125,
+            scale: 1.0,
+        }
+    }
+
+    /// Fração atual da resolução (em cada eixo)
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Resolução em que o próximo frame deve ser renderizado, pra uma janela de
+    /// `width` x `height` pixels
+    pub fn resolution(&self, width: usize, height: usize) -> (usize, usize) {
+        let scaled = |size: usize| ((size as f64 * self.scale).round() as usize).clamp(1, size);
+        (scaled(width), scaled(height))
+    }
+
+    /// Ajusta a fração da resolução a partir do tempo que o último frame levou
+    /// (renderizado na fração atual)
+    pub fn update(&mut self, elapsed: Duration) {
+        // O tempo de renderização é proporcional ao número de pixels, que é proporcional
+        // ao quadrado da fração
+        let elapsed = elapsed.as_secs_f64().max(1e-9);
+        let ideal = self.scale * (self.frame_budget.as_secs_f64() / elapsed).sqrt();
+        // Anda só metade do caminho até a fração ideal, pra não oscilar com o ruído do tempo medido
+        self.scale = ((self.scale + ideal) / 2.0).clamp(self.min_scale, 1.0);
+    }
+}
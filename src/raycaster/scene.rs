@@ -1,13 +1,123 @@
+use std::array;
+
 use glam::DVec3;
 
-use crate::{lights::Light, shapes::Shape};
+use crate::{Bvh, Intersection, PACKET_SIZE, Ray, RayPacket, lights::Light, shapes::Shape};
 
 /// Uma cena que guarda nossos objetos e luzes
 pub struct Scene {
-    /// Objetos na cena
-    pub objects: Vec<Box<dyn Shape>>,
+    /// Objetos na cena. São mudados só por `push` e `remove`, que mantêm a BVH
+    /// de acordo com eles.
+    objects: Vec<Box<dyn Shape>>,
     /// Luzes na cena
     pub lights: Vec<Box<dyn Light>>,
     /// Luz ambiente da cena
-    pub ambient_light: DVec3
+    pub ambient_light: DVec3,
+    /// Hierarquia de caixas dos objetos, usada pra acelerar os testes de interseção. Ela
+    /// guarda os índices dos objetos construídos nela, que são sempre os primeiros
+    /// `bvh.len()` de `objects`; os que vêm depois são testados um por um.
+    bvh: Bvh,
+}
+
+impl Scene {
+    /// Construtor da cena. Já constrói a BVH dos objetos.
+    pub fn new(
+        objects: Vec<Box<dyn Shape>>,
+        lights: Vec<Box<dyn Light>>,
+        ambient_light: DVec3,
+    ) -> Scene {
+        let mut scene = Scene {
+            objects,
+            lights,
+            ambient_light,
+            bvh: Bvh::new(&[]),
+        };
+        scene.rebuild_bvh();
+        scene
+    }
+
+    /// Objetos na cena
+    pub fn objects(&self) -> &[Box<dyn Shape>] {
+        &self.objects
+    }
+
+    /// Adiciona um objeto no fim da cena. Ele já é encontrado pelos raios, mas sem
+    /// aceleração até a próxima chamada de `rebuild_bvh` (que pode ser feita uma vez
+    /// só depois de adicionar vários objetos).
+    pub fn push(&mut self, object: Box<dyn Shape>) {
+        self.objects.push(object);
+    }
+
+    /// Remove e retorna o objeto de índice `index`, reconstruindo a BVH (os índices
+    /// dos objetos seguintes mudam)
+    pub fn remove(&mut self, index: usize) -> Box<dyn Shape> {
+        let object = self.objects.remove(index);
+        self.rebuild_bvh();
+        object
+    }
+
+    /// Reconstrói a BVH a partir das caixas atuais dos objetos
+    pub fn rebuild_bvh(&mut self) {
+        let bounds: Vec<_> = self.objects.iter().map(|object| object.aabb()).collect();
+        self.bvh = Bvh::new(&bounds);
+    }
+
+    /// Retorna a interseção mais próxima de um raio (dentro do intervalo dele) com os
+    /// objetos da cena (`None` se o raio não acerta nenhum)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let indexed = self
+            .bvh
+            .closest_hit(ray, |i| self.objects[i].intersects(ray));
+        // Objetos adicionados depois da construção da BVH são testados um por um
+        self.objects[self.bvh.len()..]
+            .iter()
+            .filter_map(|object| object.intersects(ray))
+            .chain(indexed)
+            .min_by(|intersection1, intersection2| intersection1.t.total_cmp(&intersection2.t))
+    }
+
+    /// `intersects` feito com um pacote de raios, que atravessa a BVH e é testado contra
+    /// os objetos com todos os raios de uma vez. Só o objeto mais próximo de cada raio é
+    /// testado de novo com o raio sozinho, pra montar a interseção completa. Pacotes que
+    /// não são coerentes (ver `RayPacket::is_coherent`) são testados raio a raio.
+    pub fn intersects_packet(&self, packet: &RayPacket) -> [Option<Intersection<'_>>; PACKET_SIZE] {
+        if !packet.is_coherent() {
+            return array::from_fn(|lane| {
+                packet
+                    .is_active(lane)
+                    .then(|| self.intersects(&packet.ray(lane)))
+                    .flatten()
+            });
+        }
+
+        let mut shrunk = packet.clone();
+        let mut closest = self.bvh.closest_hit_packet(&mut shrunk, |i, packet| {
+            self.objects[i].intersects_packet(packet)
+        });
+        // Objetos adicionados depois da construção da BVH são testados um por um
+        for (i, object) in self.objects.iter().enumerate().skip(self.bvh.len()) {
+            let hits = shrunk.shrink(object.intersects_packet(&shrunk));
+            for (closest, hit) in closest.iter_mut().zip(hits) {
+                if hit {
+                    *closest = Some(i);
+                }
+            }
+        }
+
+        array::from_fn(|lane| {
+            let object = &self.objects[closest[lane]?];
+            let ray = packet.ray(lane);
+            // Se o teste do raio sozinho discordar do pacote (por arredondamento),
+            // testa o raio contra a cena toda
+            object.intersects(&ray).or_else(|| self.intersects(&ray))
+        })
+    }
+
+    /// Diz se algum objeto da cena é atingido pelo raio no intervalo dele (ver `Shape::occludes`)
+    pub fn is_occluded(&self, ray: &Ray) -> bool {
+        self.bvh.any_hit(ray, |i| self.objects[i].occludes(ray))
+            || self.objects[self.bvh.len()..]
+                .iter()
+                .any(|object| object.occludes(ray))
+    }
 }
@@ -0,0 +1,114 @@
+//! Funções de distância com sinal (SDF) e operações para combiná-las, para usar com `shapes::Sdf`.
+//!
+//! As primitivas recebem o ponto já no sistema de coordenadas delas (centradas na origem):
+//! para mover uma primitiva, basta passar `p - centro`. As operações de domínio
+//! (`twist`, `repeat`) transformam o ponto antes dele ser passado pra primitiva.
+
+use glam::{DVec2, DVec3};
+
+/// Distância até uma esfera de raio `radius` centrada na origem
+pub fn sphere(p: DVec3, radius: f64) -> f64 {
+    p.length() - radius
+}
+
+/// Distância até uma caixa centrada na origem, com as metades das dimensões dadas
+pub fn cuboid(p: DVec3, half_extents: DVec3) -> f64 {
+    let q = p.abs() - half_extents;
+    q.max(DVec3::ZERO).length() + q.max_element().min(0.0)
+}
+
+/// Distância até um toro centrado na origem, com eixo de revolução na direção y
+pub fn torus(p: DVec3, major_radius: f64, minor_radius: f64) -> f64 {
+    let q = DVec2::new(DVec2::new(p.x, p.z).length() - major_radius, p.y);
+    q.length() - minor_radius
+}
+
+/// Distância até uma cápsula: o segmento de `a` até `b` engordado por `radius`
+pub fn capsule(p: DVec3, a: DVec3, b: DVec3, radius: f64) -> f64 {
+    let pa = p - a;
+    let ba = b - a;
+    let h = (pa.dot(ba) / ba.length_squared()).clamp(0.0, 1.0);
+    (pa - ba * h).length() - radius
+}
+
+/// Distância até o plano que passa pela origem com a normal (unitária) dada.
+/// O lado de dentro é o oposto à normal.
+pub fn plane(p: DVec3, normal: DVec3) -> f64 {
+    p.dot(normal)
+}
+
+/// União de dois sólidos
+pub fn union(d1: f64, d2: f64) -> f64 {
+    d1.min(d2)
+}
+
+/// Interseção de dois sólidos
+pub fn intersection(d1: f64, d2: f64) -> f64 {
+    d1.max(d2)
+}
+
+/// Diferença entre dois sólidos (o primeiro menos o segundo)
+pub fn difference(d1: f64, d2: f64) -> f64 {
+    d1.max(-d2)
+}
+
+/// União suave: funde os dois sólidos numa região de largura `k` em volta do encontro
+pub fn smooth_union(d1: f64, d2: f64, k: f64) -> f64 {
+    let h = (0.5 + 0.5 * (d2 - d1) / k).clamp(0.0, 1.0);
+    d2 + (d1 - d2) * h - k * h * (1.0 - h)
+}
+
+/// Diferença suave: arredonda as bordas do corte numa região de largura `k`
+pub fn smooth_difference(d1: f64, d2: f64, k: f64) -> f64 {
+    -smooth_union(-d1, d2, k)
+}
+
+/// Arredonda as quinas de um sólido, engordando ele por `radius`
+pub fn round(d: f64, radius: f64) -> f64 {
+    d - radius
+}
+
+/// Torce o espaço ao redor do eixo y: o ponto é girado por um ângulo de `k * y` radianos.
+/// Como a torção distorce as distâncias, o passo da marcha deve ser reduzido
+/// (dividindo a distância por um fator > 1) para torções fortes.
+pub fn twist(p: DVec3, k: f64) -> DVec3 {
+    let (sin, cos) = (k * p.y).sin_cos();
+    DVec3::new(cos * p.x - sin * p.z, p.y, sin * p.x + cos * p.z)
+}
+
+/// Repete o espaço infinitamente, com o período dado em cada eixo:
+/// o ponto é levado para a célula centrada na origem
+pub fn repeat(p: DVec3, period: DVec3) -> DVec3 {
+    p - period * (p / period).round()
+}
+
+/// Estimativa de distância até o Mandelbulb de potência `power` (8 é o clássico),
+/// iterando a fórmula `iterations` vezes. O fractal cabe numa esfera de raio ~1.2.
+pub fn mandelbulb(p: DVec3, power: f64, iterations: usize) -> f64 {
+    let mut z = p;
+    // Derivada de |z| em relação a p, usada na estimativa da distância
+    let mut dr = 1.0;
+    let mut r = z.length();
+    for _ in 0..iterations {
+        if !(1e-12..=2.0).contains(&r) {
+            break;
+        }
+        // z = z^power + p, com a potência em coordenadas esféricas
+        let theta = (z.z / r).acos() * power;
+        let phi = z.y.atan2(z.x) * power;
+        dr = r.powf(power - 1.0) * power * dr + 1.0;
+        z = r.powf(power)
+            * DVec3::new(
+                theta.sin() * phi.cos(),
+                theta.sin() * phi.sin(),
+                theta.cos(),
+            )
+            + p;
+        r = z.length();
+    }
+    if r < 1e-12 {
+        // A órbita ficou presa na origem: o ponto está dentro do fractal
+        return 0.0;
+    }
+    0.5 * r.ln() * r / dr
+}
@@ -0,0 +1,282 @@
+use glam::{DMat3, DVec3};
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{Shape, TriangleMesh},
+};
+
+/// Quantas vezes o domínio do patch é dividido ao meio (em u e v) na árvore de caixas
+/// usada pra achar os pontos de partida do método de Newton
+const SUBDIVISION_DEPTH: usize = 4;
+/// Número máximo de iterações do método de Newton
+const NEWTON_ITERATIONS: usize = 16;
+
+/// Superfície de Bézier bicúbica (patch), definida por uma grade 4x4 de pontos de controle:
+/// S(u, v) = Σ Σ Bᵢ(v) * Bⱼ(u) * P[i][j], com u e v entre 0 e 1 e Bₖ os polinômios de Bernstein.
+/// O patch é uma superfície aberta, então a normal sempre aponta para o lado de onde o raio veio.
+pub struct BezierPatch {
+    /// Pontos de controle: `control_points[linha][coluna]`, com as colunas na direção u
+    /// e as linhas na direção v
+    control_points: [[DVec3; 4]; 4],
+    /// Material do patch
+    pub material: Material,
+    /// Árvore de caixas que envolvem pedaços cada vez menores do patch
+    root: PatchNode,
+}
+
+/// Pedaço do domínio (u, v) de um patch, com a caixa que envolve os pontos de controle
+/// desse pedaço (que, pela propriedade do fecho convexo, envolve a superfície)
+struct PatchNode {
+    bounds: Aabb,
+    u: [f64; 2],
+    v: [f64; 2],
+    children: Vec<PatchNode>,
+}
+
+impl BezierPatch {
+    /// Construtor do patch
+    pub fn new(control_points: [[DVec3; 4]; 4], material: Material) -> BezierPatch {
+        BezierPatch {
+            control_points,
+            material,
+            root: PatchNode::new(control_points, [0.0, 1.0], [0.0, 1.0], SUBDIVISION_DEPTH),
+        }
+    }
+
+    /// Pontos de controle do patch (`control_points[linha][coluna]`)
+    pub fn control_points(&self) -> [[DVec3; 4]; 4] {
+        self.control_points
+    }
+
+    /// Ponto do patch nas coordenadas (u, v)
+    pub fn point_at(&self, u: f64, v: f64) -> DVec3 {
+        self.evaluate(u, v).0
+    }
+
+    /// Normal unitária do patch nas coordenadas (u, v): Su x Sv normalizado.
+    /// Em bordas degeneradas (onde uma das derivadas se anula, como no topo da tampa
+    /// do bule), a normal é calculada num ponto um pouco mais pra dentro do patch.
+    pub fn normal_at(&self, u: f64, v: f64) -> DVec3 {
+        let (_, su, sv) = self.evaluate(u, v);
+        su.cross(sv).try_normalize().unwrap_or_else(|| {
+            let (u, v) = (u + (0.5 - u) * 1e-4, v + (0.5 - v) * 1e-4);
+            let (_, su, sv) = self.evaluate(u, v);
+            su.cross(sv).try_normalize().unwrap_or(DVec3::Y)
+        })
+    }
+
+    /// Aproxima o patch por uma malha de triângulos, dividindo o domínio numa grade de
+    /// `resolution` x `resolution` quadrados. As normais dos vértices são as do patch.
+    pub fn tessellate(&self, resolution: usize) -> TriangleMesh {
+        let resolution = resolution.max(1);
+        let mut vertices = Vec::with_capacity((resolution + 1) * (resolution + 1));
+        let mut normals = Vec::with_capacity(vertices.capacity());
+        for row in 0..=resolution {
+            for column in 0..=resolution {
+                let u = column as f64 / resolution as f64;
+                let v = row as f64 / resolution as f64;
+                vertices.push(self.point_at(u, v));
+                normals.push(self.normal_at(u, v));
+            }
+        }
+
+        let index = |column: usize, row: usize| row * (resolution + 1) + column;
+        let mut faces = Vec::with_capacity(2 * resolution * resolution);
+        for row in 0..resolution {
+            for column in 0..resolution {
+                let (a, b) = (index(column, row), index(column + 1, row));
+                let (c, d) = (index(column, row + 1), index(column + 1, row + 1));
+                faces.push([a, b, d]);
+                faces.push([a, d, c]);
+            }
+        }
+        TriangleMesh::with_normals(vertices, normals, faces, self.material)
+    }
+
+    /// Caixa alinhada aos eixos que envolve o patch (a dos pontos de controle)
+    pub fn aabb(&self) -> Aabb {
+        self.root.bounds
+    }
+
+    /// Retorna a interseção mais próxima de um raio com o patch (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let mut closest = None;
+        self.node_intersects(&self.root, ray, &mut closest);
+        closest.map(|(t, u, v)| {
+            let mut normal = self.normal_at(u, v);
+            if normal.dot(ray.dr) > 0.0 {
+                normal = -normal;
+            }
+            Intersection {
+                t,
+                p: ray.at(t),
+                normal,
+                geometric_normal: normal,
+                material: self.material,
+                barycentric: None,
+                object: self,
+            }
+        })
+    }
+
+    /// Procura interseções nos pedaços do patch cujas caixas o raio atravessa antes da
+    /// interseção mais próxima encontrada até agora. Nas folhas da árvore, refina o
+    /// ponto de entrada na caixa pelo método de Newton.
+    fn node_intersects(&self, node: &PatchNode, ray: &Ray, closest: &mut Option<(f64, f64, f64)>) {
+        let Some((t_enter, _)) = node.bounds.intersects(ray) else {
+            return;
+        };
+        if closest.is_some_and(|(t, ..)| t_enter > t) {
+            return;
+        }
+
+        if node.children.is_empty() {
+            if let Some((t, u, v)) = self.newton(node, ray, t_enter)
+                && closest.is_none_or(|(closest_t, ..)| t < closest_t)
+            {
+                *closest = Some((t, u, v));
+            }
+            return;
+        }
+        for child in &node.children {
+            self.node_intersects(child, ray, closest);
+        }
+    }
+
+    /// Resolve S(u, v) = p0 + t*dr pelo método de Newton, começando no centro do pedaço
+    /// do patch e no t em que o raio entra na caixa dele. Só aceita soluções dentro do pedaço.
+    fn newton(&self, node: &PatchNode, ray: &Ray, t_start: f64) -> Option<(f64, f64, f64)> {
+        let mut u = (node.u[0] + node.u[1]) / 2.0;
+        let mut v = (node.v[0] + node.v[1]) / 2.0;
+        let mut t = t_start;
+        let tolerance = 1e-9 * (self.root.bounds.max - self.root.bounds.min).length().max(1.0);
+
+        for _ in 0..NEWTON_ITERATIONS {
+            let (s, su, sv) = self.evaluate(u, v);
+            let error = s - ray.at(t);
+            if error.length() < tolerance {
+                // Aceita uma pequena folga nas bordas pra não perder pontos nas emendas
+                let margin = 1e-6;
+                let inside = (node.u[0] - margin..=node.u[1] + margin).contains(&u)
+                    && (node.v[0] - margin..=node.v[1] + margin).contains(&v);
+                return (inside && ray.contains(t)).then_some((t, u.clamp(0.0, 1.0), v.clamp(0.0, 1.0)));
+            }
+            // Jacobiana de F(u, v, t) = S(u, v) - (p0 + t*dr)
+            let jacobian = DMat3::from_cols(su, sv, -ray.dr);
+            if jacobian.determinant().abs() < 1e-14 {
+                return None;
+            }
+            let step = jacobian.inverse() * error;
+            u -= step.x;
+            v -= step.y;
+            t -= step.z;
+        }
+        None
+    }
+
+    /// Retorna o ponto e as derivadas parciais Su e Sv do patch em (u, v)
+    fn evaluate(&self, u: f64, v: f64) -> (DVec3, DVec3, DVec3) {
+        let (bu, dbu) = bernstein(u);
+        let (bv, dbv) = bernstein(v);
+        let mut s = DVec3::ZERO;
+        let mut su = DVec3::ZERO;
+        let mut sv = DVec3::ZERO;
+        for (row, points) in self.control_points.iter().enumerate() {
+            for (column, &point) in points.iter().enumerate() {
+                s += point * bv[row] * bu[column];
+                su += point * bv[row] * dbu[column];
+                sv += point * dbv[row] * bu[column];
+            }
+        }
+        (s, su, sv)
+    }
+}
+
+impl Shape for BezierPatch {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
+
+impl PatchNode {
+    /// Constrói o nó e, se `depth` > 0, os 4 filhos que dividem o pedaço ao meio em u e v
+    fn new(points: [[DVec3; 4]; 4], u: [f64; 2], v: [f64; 2], depth: usize) -> PatchNode {
+        let bounds = Aabb::from_points(points.iter().flatten().copied());
+        // Folga pra caixas achatadas (patches planos)
+        let padding = 1e-9 * (bounds.max - bounds.min).length().max(1.0);
+
+        let mut children = Vec::new();
+        if depth > 0 {
+            let u_mid = (u[0] + u[1]) / 2.0;
+            let v_mid = (v[0] + v[1]) / 2.0;
+            // Divide cada linha ao meio em u, e depois cada coluna das metades ao meio em v
+            let (left, right) = split_rows(points);
+            for (half, u_range) in [(left, [u[0], u_mid]), (right, [u_mid, u[1]])] {
+                let (bottom, top) = split_rows(transpose(half));
+                children.push(PatchNode::new(
+                    transpose(bottom),
+                    u_range,
+                    [v[0], v_mid],
+                    depth - 1,
+                ));
+                children.push(PatchNode::new(
+                    transpose(top),
+                    u_range,
+                    [v_mid, v[1]],
+                    depth - 1,
+                ));
+            }
+        }
+
+        PatchNode {
+            bounds: bounds.padded(padding),
+            u,
+            v,
+            children,
+        }
+    }
+}
+
+/// Polinômios de Bernstein cúbicos em t e as suas derivadas
+pub(crate) fn bernstein(t: f64) -> ([f64; 4], [f64; 4]) {
+    let s = 1.0 - t;
+    (
+        [s * s * s, 3.0 * t * s * s, 3.0 * t * t * s, t * t * t],
+        [
+            -3.0 * s * s,
+            3.0 * s * s - 6.0 * t * s,
+            6.0 * t * s - 3.0 * t * t,
+            3.0 * t * t,
+        ],
+    )
+}
+
+/// Divide cada linha de pontos de controle (uma curva de Bézier cúbica) ao meio
+fn split_rows(points: [[DVec3; 4]; 4]) -> ([[DVec3; 4]; 4], [[DVec3; 4]; 4]) {
+    let mut left = points;
+    let mut right = points;
+    for (row, curve) in points.into_iter().enumerate() {
+        (left[row], right[row]) = split_curve(curve);
+    }
+    (left, right)
+}
+
+/// Divide uma curva de Bézier cúbica ao meio (em t = 0.5) pelo algoritmo de De Casteljau
+pub(crate) fn split_curve([p0, p1, p2, p3]: [DVec3; 4]) -> ([DVec3; 4], [DVec3; 4]) {
+    let p01 = (p0 + p1) / 2.0;
+    let p12 = (p1 + p2) / 2.0;
+    let p23 = (p2 + p3) / 2.0;
+    let p012 = (p01 + p12) / 2.0;
+    let p123 = (p12 + p23) / 2.0;
+    let mid = (p012 + p123) / 2.0;
+    ([p0, p01, p012, mid], [mid, p123, p23, p3])
+}
+
+/// Troca as linhas e colunas da grade de pontos de controle
+fn transpose(points: [[DVec3; 4]; 4]) -> [[DVec3; 4]; 4] {
+    std::array::from_fn(|row| std::array::from_fn(|column| points[column][row]))
+}
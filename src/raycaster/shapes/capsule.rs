@@ -0,0 +1,137 @@
+use glam::DVec3;
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{Shape, Span},
+    solvers::solve_quadratic,
+};
+
+/// Cápsula: um cilindro com semiesferas nas duas pontas, ou seja, todos os pontos a uma
+/// distância de no máximo `radius` do segmento que vai de `a` até `b`
+pub struct Capsule {
+    /// Centro da semiesfera de uma das pontas
+    pub a: DVec3,
+    /// Centro da semiesfera da outra ponta
+    pub b: DVec3,
+    /// Raio da cápsula
+    pub radius: f64,
+    /// Material da cápsula
+    pub material: Material,
+}
+
+impl Capsule {
+    /// Construtor da cápsula
+    pub fn new(a: DVec3, b: DVec3, radius: f64, material: Material) -> Capsule {
+        Capsule {
+            a,
+            b,
+            radius,
+            material,
+        }
+    }
+
+    /// Caixa alinhada aos eixos que envolve a cápsula
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points([self.a, self.b]).padded(self.radius)
+    }
+
+    /// Retorna a interseção mais próxima de um raio com a cápsula (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.line_hits(ray)
+            .into_iter()
+            .filter(|&t| ray.contains(t))
+            .min_by(|t1, t2| t1.total_cmp(t2))
+            .map(|t| self.intersection_at(ray, t))
+    }
+
+    /// Retorna o intervalo do raio que está dentro da cápsula (vazio se não há interseção)
+    pub fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        // A cápsula é convexa: a reta entra na primeira interseção e sai na última
+        let hits = self.line_hits(ray);
+        let entry = hits.iter().copied().min_by(|t1, t2| t1.total_cmp(t2));
+        let exit = hits.iter().copied().max_by(|t1, t2| t1.total_cmp(t2));
+        match (entry, exit) {
+            (Some(entry), Some(exit)) if entry < exit => vec![Span {
+                entry: self.intersection_at(ray, entry),
+                exit: self.intersection_at(ray, exit),
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Valores de t (de qualquer sinal) em que a reta do raio cruza a borda da cápsula
+    fn line_hits(&self, ray: &Ray) -> Vec<f64> {
+        let (axis, height) = self.axis();
+        let mut hits = Vec::new();
+
+        // Superfície cilíndrica, entre as duas pontas
+        let w = ray.p0 - self.a;
+        let mdr = ray.dr.reject_from_normalized(axis);
+        let mw = w.reject_from_normalized(axis);
+        if let Some(roots) = solve_quadratic(
+            mdr.length_squared(),
+            2.0 * mdr.dot(mw),
+            mw.length_squared() - self.radius * self.radius,
+        ) {
+            hits.extend(roots.into_iter().filter(|&t| {
+                let h = (ray.at(t) - self.a).dot(axis);
+                (0.0..=height).contains(&h)
+            }));
+        }
+
+        // Semiesferas: só a metade de cada esfera que fica pra fora do cilindro
+        // (abaixo da altura 0, em `a`, e acima da altura do segmento, em `b`)
+        for (center, center_height, side) in [(self.a, 0.0, -1.0), (self.b, height, 1.0)] {
+            let oc = ray.p0 - center;
+            if let Some(roots) = solve_quadratic(
+                ray.dr.length_squared(),
+                2.0 * ray.dr.dot(oc),
+                oc.length_squared() - self.radius * self.radius,
+            ) {
+                hits.extend(roots.into_iter().filter(|&t| {
+                    let h = (ray.at(t) - self.a).dot(axis);
+                    (h - center_height) * side > 0.0
+                }));
+            }
+        }
+        hits
+    }
+
+    /// Direção unitária de `a` até `b` e a distância entre eles
+    fn axis(&self) -> (DVec3, f64) {
+        let ab = self.b - self.a;
+        (ab.try_normalize().unwrap_or(DVec3::Y), ab.length())
+    }
+
+    /// Constrói a interseção no ponto t do raio. A normal aponta do ponto mais
+    /// próximo do segmento até o ponto, o que a deixa contínua nas emendas.
+    fn intersection_at(&self, ray: &Ray, t: f64) -> Intersection<'_> {
+        let (axis, height) = self.axis();
+        let p = ray.at(t);
+        let closest = self.a + axis * (p - self.a).dot(axis).clamp(0.0, height);
+        let normal = (p - closest).normalize();
+        Intersection {
+            t,
+            p,
+            normal,
+            geometric_normal: normal,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        }
+    }
+}
+
+impl Shape for Capsule {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.spans(ray)
+    }
+}
@@ -1,6 +1,14 @@
 use glam::DVec3;
 
-use crate::{Intersection, Material, Ray, shapes::Shape};
+use crate::{
+    Aabb, Intersection, Lanes, Lanes3, Material, Ray, RayPacket,
+    shapes::{
+        Shape, Span,
+        csg::spans_from_hits,
+        disk::{disk_aabb, disk_intersects, disk_intersects_packet, disk_line_intersects},
+    },
+    solvers::{solve_quadratic, solve_quadratic_packet},
+};
 
 /// Cilindro definido pelo centro de sua base, direção, altura e raio
 pub struct Cilinder {
@@ -42,9 +50,16 @@ impl Cilinder {
         }
     }
 
+    /// Caixa alinhada aos eixos que envolve o cilindro (a das suas duas bases)
+    pub fn aabb(&self) -> Aabb {
+        let dc = self.dc.normalize();
+        let ct = self.cb + dc * self.height;
+        disk_aabb(self.cb, dc, self.radius).union(&disk_aabb(ct, dc, self.radius))
+    }
+
     /// Retorna a interseção mais próxima entre a superfície cilíndrica,
     /// a base e o topo do cilindro (`None` se não há interseção).
-    pub fn intersects(&self, ray: &Ray) -> Option<Intersection> {
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
         // Testa interseção com a superfície, a base e o topo do cilindro
         let surface_intersection = self.surface_intersects(ray);
         let base_intersection = if self.has_base {
@@ -65,9 +80,80 @@ impl Cilinder {
             .min_by(|intersection1, intersection2| intersection1.t.total_cmp(&intersection2.t))
     }
 
-    /// Retorna a interseção de menor t positivo dum raio com a superfície do cilindro
-    /// (`None` se não há interseção)
-    fn surface_intersects(&self, ray: &Ray) -> Option<Intersection> {
+    /// `intersects` feito em todos os raios de um pacote ao mesmo tempo: retorna o t de
+    /// cada raio (infinito nos que não acertam o cilindro)
+    pub fn intersects_packet(&self, packet: &RayPacket) -> Lanes {
+        // Mesma equação de `surface_roots`, em cada raio
+        let w = packet.p0 - Lanes3::splat(self.cb);
+        let mdr = packet.dr.reject_from_normalized(self.dc);
+        let mw = w.reject_from_normalized(self.dc);
+        let ([t1, t2], real) = solve_quadratic_packet(
+            mdr.length_squared(),
+            mdr.dot(mw) * 2.0,
+            mw.length_squared() - self.radius * self.radius,
+        );
+        // Como em `surface_intersects`: a raiz de menor t no intervalo, se ela está entre a base e o topo
+        let t = packet.clip(t1, real).min(packet.clip(t2, real));
+        let height = (packet.at(t) - Lanes3::splat(self.cb)).dot(Lanes3::splat(self.dc));
+        let mut closest = Lanes::select(height.within(0.0, self.height), t, Lanes::INFINITY);
+
+        if self.has_base {
+            closest = closest.min(disk_intersects_packet(packet, self.cb, -self.dc, self.radius));
+        }
+        if self.has_top {
+            let ct = self.cb + self.dc * self.height;
+            closest = closest.min(disk_intersects_packet(packet, ct, self.dc, self.radius));
+        }
+        closest
+    }
+
+    /// Retorna os intervalos do raio que estão dentro do cilindro, tratado como um
+    /// sólido fechado (com base e topo, mesmo que `has_base` ou `has_top` sejam falsos)
+    pub fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        // Junta todas as interseções da reta do raio com a borda do cilindro
+        let mut hits = Vec::new();
+        // Uma raiz dupla é um raio tangente à superfície, que não entra no cilindro
+        if let Some([t1, t2]) = self.surface_roots(ray)
+            && t1 != t2
+        {
+            hits.extend(
+                [t1, t2]
+                    .into_iter()
+                    .filter_map(|t| self.surface_intersection_at(ray, t)),
+            );
+        }
+        let ct = self.cb + self.dc * self.height;
+        for (center, normal) in [(self.cb, -self.dc), (ct, self.dc)] {
+            if let Some(t) = disk_line_intersects(ray, center, normal, self.radius) {
+                hits.push(Intersection {
+                    t,
+                    p: ray.at(t),
+                    normal,
+                    geometric_normal: normal,
+                    material: self.material,
+                    barycentric: None,
+                    object: self,
+                });
+            }
+        }
+        spans_from_hits(hits)
+    }
+
+    /// Retorna a interseção de menor t (dentro do intervalo do raio) dum raio com a
+    /// superfície do cilindro (`None` se não há interseção)
+    fn surface_intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        // Pega a interseção válida de menor t
+        let [t1, t2] = self.surface_roots(ray)?;
+        [t1, t2]
+            .into_iter()
+            .filter(|t| ray.contains(*t)) // Filtra só os T's dentro do intervalo do raio
+            .min_by(|t1, t2| t1.total_cmp(t2)) // Pega o menor t
+            .and_then(|t| self.surface_intersection_at(ray, t))
+    }
+
+    /// Valores de t (de qualquer sinal) em que a reta do raio cruza a superfície
+    /// cilíndrica infinita
+    fn surface_roots(&self, ray: &Ray) -> Option<[f64; 2]> {
         // Resolve a equação do segudo grau para |M*CbP| = r
         // (o ponto P de interseção do raio deve estar a uma distância
         // r do eixo do cilindro)
@@ -80,83 +166,75 @@ impl Cilinder {
         let b = 2.0 * mdr.dot(mw);
         let c = mw.length_squared() - self.radius * self.radius;
 
-        let delta = b * b - 4.0 * a * c;
-        if delta < 0.0 {
-            return None;
-        }
-
-        // Pega a interseção válida de menor t positivo
-        let t1 = (-b - delta.sqrt()) / (2.0 * a);
-        let t2 = (-b + delta.sqrt()) / (2.0 * a);
-        [t1, t2]
-            .into_iter()
-            .filter(|t| *t > 0.0) // Filtra só os T's positivos
-            .min_by(|t1, t2| t1.total_cmp(t2)) // Pega o menor t positivo
-            .and_then(|t| {
-                let p = ray.at(t);
-                // Testa se o ponto de interseção está entre a base e o topo do cilindro
-                let intersection_height = (p - self.cb).dot(self.dc);
-                if intersection_height < 0.0 || intersection_height > self.height {
-                    return None;
-                }
-                // Normal = Vetor M*CbP normalizado
-                let n = (p - self.cb).reject_from_normalized(self.dc).normalize();
-                Some(Intersection {
-                    t,
-                    p,
-                    normal: n,
-                    material: self.material,
-                    object: self,
-                })
-            })
+        solve_quadratic(a, b, c)
     }
 
-    /// Teste de interseção raio-plano com a base do cilindro
-    fn base_intersects(&self, ray: &Ray) -> Option<Intersection> {
-        let bottom = ray.dr.dot(-self.dc);
-        if bottom.abs() < 1e-8 {
-            return None;
-        }
-        let t = -(ray.p0 - self.cb).dot(-self.dc) / bottom;
-        // O ponto de interseção deve estar a uma distância de no máximo r do centro da base
+    /// Constrói a interseção com a superfície no ponto t do raio
+    /// (`None` se o ponto está abaixo da base ou acima do topo)
+    fn surface_intersection_at(&self, ray: &Ray, t: f64) -> Option<Intersection<'_>> {
         let p = ray.at(t);
-        if t < 0.0 || (p - self.cb).length_squared() > self.radius * self.radius {
+        // Testa se o ponto de interseção está entre a base e o topo do cilindro
+        let intersection_height = (p - self.cb).dot(self.dc);
+        if intersection_height < 0.0 || intersection_height > self.height {
             return None;
         }
+        // Normal = Vetor M*CbP normalizado
+        let n = (p - self.cb).reject_from_normalized(self.dc).normalize();
+        Some(Intersection {
+            t,
+            p,
+            normal: n,
+            geometric_normal: n,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        })
+    }
+
+    /// Teste de interseção raio-disco com a base do cilindro
+    fn base_intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let t = disk_intersects(ray, self.cb, -self.dc, self.radius)?;
         Some(Intersection {
-            t: t,
-            p: p,
+            t,
+            p: ray.at(t),
             normal: -self.dc,
+            geometric_normal: -self.dc,
             material: self.material,
+            barycentric: None,
             object: self,
         })
     }
 
-    /// Teste de interseção raio-plano com o topo do cilindro
-    fn top_intersects(&self, ray: &Ray) -> Option<Intersection> {
-        let bottom = ray.dr.dot(self.dc);
-        if bottom.abs() < 1e-8 {
-            return None;
-        }
+    /// Teste de interseção raio-disco com o topo do cilindro
+    fn top_intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
         let ct = self.cb + self.dc * self.height;
-        let t = -(ray.p0 - ct).dot(self.dc) / bottom;
-        let p = ray.at(t);
-        // O ponto de interseção deve estar a uma distância de no máximo r do centro do topo
-        if t < 0.0 || (p - ct).length_squared() > self.radius * self.radius {
-            return None;
-        }
+        let t = disk_intersects(ray, ct, self.dc, self.radius)?;
         Some(Intersection {
-            t: t,
-            p: p,
+            t,
+            p: ray.at(t),
             normal: self.dc,
+            geometric_normal: self.dc,
             material: self.material,
+            barycentric: None,
             object: self,
         })
     }
 }
 
 impl Shape for Cilinder {
-    fn intersects(&self, ray: &Ray) -> Option<Intersection> {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
         self.intersects(ray)
     }
+
+    fn intersects_packet(&self, packet: &RayPacket) -> Lanes {
+        self.intersects_packet(packet)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.spans(ray)
+    }
 }
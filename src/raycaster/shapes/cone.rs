@@ -1,4 +1,12 @@
-use crate::{Intersection, Material, Ray, shapes::Shape};
+use crate::{
+    Aabb, Intersection, Lanes, Lanes3, Material, Ray, RayPacket,
+    shapes::{
+        Shape, Span,
+        csg::spans_from_hits,
+        disk::{disk_aabb, disk_intersects, disk_intersects_packet, disk_line_intersects},
+    },
+    solvers::{solve_quadratic, solve_quadratic_packet},
+};
 use glam::DVec3;
 
 /// Cone definido pelo centro de sua base, direção, altura e raio da base
@@ -37,9 +45,16 @@ impl Cone {
         }
     }
 
+    /// Caixa alinhada aos eixos que envolve o cone (a da base e do vértice)
+    pub fn aabb(&self) -> Aabb {
+        let dc = self.dc.normalize();
+        let vertice = self.cb + dc * self.height;
+        disk_aabb(self.cb, dc, self.radius).union(&Aabb::new(vertice, vertice))
+    }
+
     /// Retorna a interseção mais próxima entre a superfície cônica
     /// e a base do cone (`None` se não há interseção).
-    pub fn intersects(&self, ray: &Ray) -> Option<Intersection> {
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
         // Testa interseção com a
         let surface_intersection = self.surface_intersects(ray);
         let base_intersection = if self.has_base {
@@ -55,91 +70,163 @@ impl Cone {
             .min_by(|intersection1, intersection2| intersection1.t.total_cmp(&intersection2.t))
     }
 
-    /// Retorna a interseção de menor t positivo dum raio com a superfície do cone
-    /// (`None` se não há interseção).
-    fn surface_intersects(&self, ray: &Ray) -> Option<Intersection> {
-        // Resolve a equação de segundo grau em t
-        // para a semelhança de triângulos |MCbP| / |hdc - QCbP| = r / h
-        let w = ray.p0 - self.cb;
-
-        let mdr = ray.dr.reject_from_normalized(self.dc);
+    /// `intersects` feito em todos os raios de um pacote ao mesmo tempo: retorna o t de
+    /// cada raio (infinito nos que não acertam o cone)
+    pub fn intersects_packet(&self, packet: &RayPacket) -> Lanes {
+        // Mesma equação de `surface_roots`, em cada raio
+        let w = packet.p0 - Lanes3::splat(self.cb);
+        let mdr = packet.dr.reject_from_normalized(self.dc);
         let mw = w.reject_from_normalized(self.dc);
-        let qdr = ray.dr.project_onto_normalized(self.dc);
+        let qdr = packet.dr.project_onto_normalized(self.dc);
         let qw = w.project_onto_normalized(self.dc);
-        let hdc = self.height * self.dc;
+        let hdc = Lanes3::splat(self.height * self.dc);
 
         let h2 = self.height * self.height;
         let r2 = self.radius * self.radius;
 
-        let a = h2 * mdr.length_squared() - r2 * qdr.length_squared();
-        let b = 2.0 * (h2 * mdr.dot(mw) + r2 * qdr.dot(hdc - qw));
-        let c = h2 * mw.length_squared() - r2 * (qw - hdc).length_squared();
+        let a = mdr.length_squared() * h2 - qdr.length_squared() * r2;
+        let b = (mdr.dot(mw) * h2 + qdr.dot(hdc - qw) * r2) * 2.0;
+        let c = mw.length_squared() * h2 - (qw - hdc).length_squared() * r2;
+        let (roots, real) = solve_quadratic_packet(a, b, c);
 
-        let delta = b * b - 4.0 * a * c;
-        if delta < 0.0 {
-            return None;
+        // Como em `surface_intersects`: as duas raízes são testadas antes de pegar a menor
+        let mut closest = Lanes::INFINITY;
+        for t in roots {
+            let t = packet.clip(t, real);
+            let height = (packet.at(t) - Lanes3::splat(self.cb)).dot(Lanes3::splat(self.dc));
+            let inside = height.within(0.0, self.height);
+            closest = closest.min(Lanes::select(inside, t, Lanes::INFINITY));
         }
 
-        // Pega a interseção válida de menor t positivo
-        let t1 = (-b - delta.sqrt()) / (2.0 * a);
-        let t2 = (-b + delta.sqrt()) / (2.0 * a);
+        if self.has_base {
+            closest = closest.min(disk_intersects_packet(packet, self.cb, -self.dc, self.radius));
+        }
+        closest
+    }
+
+    /// Retorna os intervalos do raio que estão dentro do cone, tratado como um
+    /// sólido fechado (com base, mesmo que `has_base` seja falso)
+    pub fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        // Junta todas as interseções da reta do raio com a borda do cone
+        let mut hits = Vec::new();
+        // Uma raiz dupla é um raio tangente à superfície, que não entra no cone
+        if let Some([t1, t2]) = self.surface_roots(ray)
+            && t1 != t2
+        {
+            hits.extend(
+                [t1, t2]
+                    .into_iter()
+                    .filter_map(|t| self.surface_intersection_at(ray, t)),
+            );
+        }
+        if let Some(t) = disk_line_intersects(ray, self.cb, -self.dc, self.radius) {
+            hits.push(Intersection {
+                t,
+                p: ray.at(t),
+                normal: -self.dc,
+                geometric_normal: -self.dc,
+                material: self.material,
+                barycentric: None,
+                object: self,
+            });
+        }
+        spans_from_hits(hits)
+    }
+
+    /// Retorna a interseção de menor t (dentro do intervalo do raio) dum raio com a
+    /// superfície do cone (`None` se não há interseção).
+    fn surface_intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        // Pega a interseção válida de menor t
+        let [t1, t2] = self.surface_roots(ray)?;
         // A gente tem que testar as 2 interseções antes de pegar o t mínimo,
         // por conta da peculiaridade da superfície cônica se estender ao infinito
         // pra cima, podendo causar problemas de não renderizar o cone por conta da
         // "interseção fantasma" com a superfície de cima etc.
         [t1, t2]
             .into_iter()
-            .filter_map(|t| {
-                // Pega só as interseções de T positivo
-                if t < 0.0 {
-                    return None;
-                }
-                // Testa se o ponto de interseção está entre a base e o topo do cone
-                let p = ray.at(t);
-                let intersection_height = (p - self.cb).dot(self.dc);
-                if intersection_height < 0.0 || intersection_height > self.height {
-                    return None;
-                }
-                // Normal = Vetor M_pv*dc normalizado
-                let vertice = self.cb + self.dc * self.height;
-                let pv = (p - vertice).normalize();
-                let n = (self.dc).reject_from_normalized(pv).normalize();
-                Some(Intersection {
-                    t,
-                    p,
-                    normal: n,
-                    material: self.material,
-                    object: self,
-                })
-            })
+            // Pega só as interseções dentro do intervalo do raio
+            .filter(|&t| ray.contains(t))
+            .filter_map(|t| self.surface_intersection_at(ray, t))
             // Pega a interseção com menor t
             .min_by(|intersection1, intersection2| intersection1.t.total_cmp(&intersection2.t))
     }
 
-    /// Teste de interseção raio-plano com a base do cone
-    fn base_intersects(&self, ray: &Ray) -> Option<Intersection> {
-        let bottom = ray.dr.dot(-self.dc);
-        if bottom.abs() < 1e-8 {
-            return None;
-        }
-        let t = -(ray.p0 - self.cb).dot(-self.dc) / bottom;
-        // O ponto de interseção deve estar a uma distância de no máximo r do centro da base
+    /// Valores de t (de qualquer sinal) em que a reta do raio cruza a superfície
+    /// cônica infinita
+    fn surface_roots(&self, ray: &Ray) -> Option<[f64; 2]> {
+        // Resolve a equação de segundo grau em t
+        // para a semelhança de triângulos |MCbP| / |hdc - QCbP| = r / h
+        let w = ray.p0 - self.cb;
+
+        let mdr = ray.dr.reject_from_normalized(self.dc);
+        let mw = w.reject_from_normalized(self.dc);
+        let qdr = ray.dr.project_onto_normalized(self.dc);
+        let qw = w.project_onto_normalized(self.dc);
+        let hdc = self.height * self.dc;
+
+        let h2 = self.height * self.height;
+        let r2 = self.radius * self.radius;
+
+        let a = h2 * mdr.length_squared() - r2 * qdr.length_squared();
+        let b = 2.0 * (h2 * mdr.dot(mw) + r2 * qdr.dot(hdc - qw));
+        let c = h2 * mw.length_squared() - r2 * (qw - hdc).length_squared();
+
+        solve_quadratic(a, b, c)
+    }
+
+    /// Constrói a interseção com a superfície no ponto t do raio
+    /// (`None` se o ponto está abaixo da base ou acima do vértice)
+    fn surface_intersection_at(&self, ray: &Ray, t: f64) -> Option<Intersection<'_>> {
+        // Testa se o ponto de interseção está entre a base e o topo do cone
         let p = ray.at(t);
-        if t < 0.0 || (p - self.cb).length_squared() > self.radius * self.radius {
+        let intersection_height = (p - self.cb).dot(self.dc);
+        if intersection_height < 0.0 || intersection_height > self.height {
             return None;
         }
+        // Normal = Vetor M_pv*dc normalizado
+        let vertice = self.cb + self.dc * self.height;
+        let pv = (p - vertice).normalize();
+        let n = (self.dc).reject_from_normalized(pv).normalize();
+        Some(Intersection {
+            t,
+            p,
+            normal: n,
+            geometric_normal: n,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        })
+    }
+
+    /// Teste de interseção raio-disco com a base do cone
+    fn base_intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let t = disk_intersects(ray, self.cb, -self.dc, self.radius)?;
         Some(Intersection {
-            t: t,
-            p: p,
+            t,
+            p: ray.at(t),
             normal: -self.dc,
+            geometric_normal: -self.dc,
             material: self.material,
+            barycentric: None,
             object: self,
         })
     }
 }
 
 impl Shape for Cone {
-    fn intersects(&self, ray: &Ray) -> Option<Intersection> {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
         self.intersects(ray)
     }
+
+    fn intersects_packet(&self, packet: &RayPacket) -> Lanes {
+        self.intersects_packet(packet)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.spans(ray)
+    }
 }
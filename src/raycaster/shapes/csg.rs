@@ -0,0 +1,163 @@
+use crate::{Aabb, Intersection, Ray, shapes::Shape};
+
+/// Intervalo de um raio que está dentro de um sólido, com as interseções
+/// na entrada e na saída do sólido (os t podem ser infinitos em sólidos ilimitados)
+pub struct Span<'a> {
+    /// Interseção onde o raio entra no sólido
+    pub entry: Intersection<'a>,
+    /// Interseção onde o raio sai do sólido
+    pub exit: Intersection<'a>,
+}
+
+/// Operação booleana entre os dois sólidos de um `Csg`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CsgOperation {
+    /// Pontos que estão em pelo menos um dos sólidos
+    Union,
+    /// Pontos que estão nos dois sólidos
+    Intersection,
+    /// Pontos que estão no primeiro sólido, mas não no segundo
+    Difference,
+}
+
+/// Sólido construído pela combinação de outros dois (Constructive Solid Geometry).
+/// Os dois sólidos precisam implementar `Shape::spans`.
+pub struct Csg {
+    /// Primeiro sólido
+    pub left: Box<dyn Shape>,
+    /// Segundo sólido (o que é subtraído, na diferença)
+    pub right: Box<dyn Shape>,
+    /// Operação que combina os dois sólidos
+    pub operation: CsgOperation,
+}
+
+impl Csg {
+    /// Construtor do sólido combinado
+    pub fn new(left: Box<dyn Shape>, right: Box<dyn Shape>, operation: CsgOperation) -> Csg {
+        Csg {
+            left,
+            right,
+            operation,
+        }
+    }
+
+    /// União de dois sólidos
+    pub fn union(left: Box<dyn Shape>, right: Box<dyn Shape>) -> Csg {
+        Csg::new(left, right, CsgOperation::Union)
+    }
+
+    /// Interseção de dois sólidos
+    pub fn intersection(left: Box<dyn Shape>, right: Box<dyn Shape>) -> Csg {
+        Csg::new(left, right, CsgOperation::Intersection)
+    }
+
+    /// Diferença entre dois sólidos (`left` menos `right`)
+    pub fn difference(left: Box<dyn Shape>, right: Box<dyn Shape>) -> Csg {
+        Csg::new(left, right, CsgOperation::Difference)
+    }
+
+    /// Caixa alinhada aos eixos que envolve o resultado da operação
+    pub fn aabb(&self) -> Aabb {
+        let (left, right) = (self.left.aabb(), self.right.aabb());
+        match self.operation {
+            CsgOperation::Union => left.union(&right),
+            CsgOperation::Intersection => left.intersection(&right),
+            // Tirar partes do sólido da esquerda não aumenta ele
+            CsgOperation::Difference => left,
+        }
+    }
+
+    /// Retorna a interseção mais próxima (dentro do intervalo do raio) de um raio com
+    /// a borda do sólido combinado (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.spans(ray)
+            .into_iter()
+            .flat_map(|span| [span.entry, span.exit])
+            .find(|intersection| ray.contains(intersection.t))
+    }
+
+    /// Retorna os intervalos do raio que estão dentro do sólido combinado
+    pub fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        // Bordas dos intervalos dos dois sólidos, marcadas com o sólido de origem
+        // e se são entradas ou saídas
+        let mut boundaries: Vec<(Intersection, bool, bool)> = Vec::new();
+        for (shape, is_left) in [(&self.left, true), (&self.right, false)] {
+            for span in shape.spans(ray) {
+                boundaries.push((span.entry, is_left, true));
+                boundaries.push((span.exit, is_left, false));
+            }
+        }
+        boundaries.sort_by(|(i1, ..), (i2, ..)| i1.t.total_cmp(&i2.t));
+
+        // Percorre as bordas em ordem, acompanhando se o raio está dentro de cada sólido
+        let mut inside_left = false;
+        let mut inside_right = false;
+        let mut entry: Option<Intersection> = None;
+        let mut spans = Vec::new();
+        for (mut boundary, is_left, is_entry) in boundaries {
+            let was_inside = self.contains(inside_left, inside_right);
+            if is_left {
+                inside_left = is_entry;
+            } else {
+                inside_right = is_entry;
+            }
+            let is_inside = self.contains(inside_left, inside_right);
+            if was_inside == is_inside {
+                continue;
+            }
+
+            // Na diferença, as bordas do sólido subtraído viram bordas do resultado
+            // com o lado de dentro trocado, então a normal é invertida
+            if !is_left && self.operation == CsgOperation::Difference {
+                boundary.normal = -boundary.normal;
+                boundary.geometric_normal = -boundary.geometric_normal;
+            }
+            if is_inside {
+                entry = Some(boundary);
+            } else if let Some(entry) = entry.take() {
+                spans.push(Span {
+                    entry,
+                    exit: boundary,
+                });
+            }
+        }
+        spans
+    }
+
+    /// Diz se um ponto está dentro do sólido combinado, sabendo se ele está
+    /// dentro de cada um dos dois sólidos
+    fn contains(&self, inside_left: bool, inside_right: bool) -> bool {
+        match self.operation {
+            CsgOperation::Union => inside_left || inside_right,
+            CsgOperation::Intersection => inside_left && inside_right,
+            CsgOperation::Difference => inside_left && !inside_right,
+        }
+    }
+}
+
+impl Shape for Csg {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.spans(ray)
+    }
+}
+
+/// Monta os intervalos de um sólido fechado a partir de todas as interseções da
+/// reta do raio com a sua borda: ordenadas por t, elas alternam entre entrada e saída
+pub(crate) fn spans_from_hits(mut hits: Vec<Intersection<'_>>) -> Vec<Span<'_>> {
+    hits.sort_by(|i1, i2| i1.t.total_cmp(&i2.t));
+    let mut hits = hits.into_iter();
+    let mut spans = Vec::new();
+    // Uma interseção sem par (erro numérico numa quina) é descartada
+    while let (Some(entry), Some(exit)) = (hits.next(), hits.next()) {
+        spans.push(Span { entry, exit });
+    }
+    spans
+}
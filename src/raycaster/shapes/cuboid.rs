@@ -0,0 +1,158 @@
+use glam::{DMat3, DVec3};
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{Shape, Span},
+};
+
+/// Caixa (paralelepípedo retângulo) definida pelo seu centro,
+/// seus 3 eixos e as metades das suas dimensões em cada eixo
+pub struct Cuboid {
+    /// Centro da caixa
+    pub center: DVec3,
+    /// Eixos da caixa (colunas ortonormais)
+    pub axes: DMat3,
+    /// Metade do tamanho da caixa em cada um dos eixos
+    pub half_extents: DVec3,
+    /// Material da caixa
+    pub material: Material,
+}
+
+impl Cuboid {
+    /// Construtor da caixa alinhada aos eixos x, y e z, definida por suas quinas opostas
+    pub fn new(min: DVec3, max: DVec3, material: Material) -> Cuboid {
+        Cuboid {
+            center: (min + max) / 2.0,
+            axes: DMat3::IDENTITY,
+            half_extents: (max - min).abs() / 2.0,
+            material,
+        }
+    }
+
+    /// Construtor da caixa orientada. Os eixos são normalizados,
+    /// e devem ser perpendiculares entre si.
+    pub fn oriented(
+        center: DVec3,
+        axes: [DVec3; 3],
+        half_extents: DVec3,
+        material: Material,
+    ) -> Cuboid {
+        Cuboid {
+            center,
+            axes: DMat3::from_cols(
+                axes[0].normalize(),
+                axes[1].normalize(),
+                axes[2].normalize(),
+            ),
+            half_extents,
+            material,
+        }
+    }
+
+    /// Caixa alinhada aos eixos que envolve a caixa orientada
+    pub fn aabb(&self) -> Aabb {
+        // Em cada eixo do mundo, a caixa se estende pela soma das projeções das suas metades
+        let half_extents = self.axes.x_axis.abs() * self.half_extents.x
+            + self.axes.y_axis.abs() * self.half_extents.y
+            + self.axes.z_axis.abs() * self.half_extents.z;
+        Aabb::from_center(self.center, half_extents)
+    }
+
+    /// Retorna a interseção mais próxima de um raio com a caixa (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let [near, far] = self.slab_intersects(ray)?;
+        // Se o começo do intervalo do raio está dentro da caixa, a interseção é na saída
+        [near, far]
+            .into_iter()
+            .find(|&(t, _)| ray.contains(t))
+            .map(|(t, normal)| self.intersection_at(ray, t, normal))
+    }
+
+    /// Retorna o intervalo do raio que está dentro da caixa (vazio se não há interseção)
+    pub fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        match self.slab_intersects(ray) {
+            Some([(t_near, near_normal), (t_far, far_normal)]) => vec![Span {
+                entry: self.intersection_at(ray, t_near, near_normal),
+                exit: self.intersection_at(ray, t_far, far_normal),
+            }],
+            None => Vec::new(),
+        }
+    }
+
+    /// Retorna os t (de qualquer sinal) e as normais onde a reta do raio
+    /// entra e sai da caixa (`None` se ela não passa pela caixa)
+    fn slab_intersects(&self, ray: &Ray) -> Option<[(f64, DVec3); 2]> {
+        // Leva o raio pro sistema de coordenadas da caixa,
+        // onde ela é a região -half_extents <= p <= half_extents
+        let to_local = self.axes.transpose();
+        let p0 = to_local * (ray.p0 - self.center);
+        let dr = to_local * ray.dr;
+
+        // Método das "slabs": o raio está dentro da caixa no intervalo
+        // em que ele está entre os dois planos de cada eixo
+        let mut t_near = f64::NEG_INFINITY;
+        let mut t_far = f64::INFINITY;
+        let mut near_normal = DVec3::ZERO;
+        let mut far_normal = DVec3::ZERO;
+        for axis in 0..3 {
+            let (p, d, h) = (p0[axis], dr[axis], self.half_extents[axis]);
+            if d.abs() < 1e-12 {
+                // Raio paralelo aos planos desse eixo: tem que começar entre eles
+                if p.abs() > h {
+                    return None;
+                }
+                continue;
+            }
+            // Interseções com os planos -h e +h do eixo
+            let t1 = (-h - p) / d;
+            let t2 = (h - p) / d;
+            // Normal do plano por onde o raio entra nesse eixo
+            let mut normal = DVec3::ZERO;
+            normal[axis] = -d.signum();
+
+            if t1.min(t2) > t_near {
+                t_near = t1.min(t2);
+                near_normal = normal;
+            }
+            if t1.max(t2) < t_far {
+                t_far = t1.max(t2);
+                far_normal = -normal;
+            }
+        }
+
+        if t_near > t_far {
+            return None;
+        }
+        Some([
+            (t_near, self.axes * near_normal),
+            (t_far, self.axes * far_normal),
+        ])
+    }
+
+    /// Constrói o struct da interseção no ponto t do raio
+    fn intersection_at(&self, ray: &Ray, t: f64, normal: DVec3) -> Intersection<'_> {
+        Intersection {
+            t,
+            p: ray.at(t),
+            normal,
+            geometric_normal: normal,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        }
+    }
+}
+
+impl Shape for Cuboid {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.spans(ray)
+    }
+}
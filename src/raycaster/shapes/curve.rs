@@ -0,0 +1,290 @@
+use glam::{DVec2, DVec3};
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{
+        Shape,
+        bezier::{bernstein, split_curve},
+    },
+};
+
+/// Profundidade máxima da subdivisão da curva no teste de interseção
+const MAX_DEPTH: usize = 10;
+
+/// Como a largura de uma `Curve` é renderizada
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CurveMode {
+    /// Fita plana, sempre virada para o raio (mais barata, boa para fios finos)
+    Ribbon,
+    /// Tubo de seção circular, com a largura sendo o diâmetro
+    Tube,
+}
+
+/// Curva de Bézier cúbica com largura variável, para cabelo, pelos, grama e cabos.
+/// A largura varia linearmente de `widths[0]` no começo até `widths[1]` no fim da curva.
+/// As normais são sempre perpendiculares à tangente da curva.
+pub struct Curve {
+    /// Pontos de controle da curva (na forma de Bézier)
+    pub control_points: [DVec3; 4],
+    /// Largura da curva no começo e no fim
+    pub widths: [f64; 2],
+    /// Modo de renderização da largura
+    pub mode: CurveMode,
+    /// Material da curva
+    pub material: Material,
+}
+
+impl Curve {
+    /// Construtor da curva a partir dos pontos de controle de Bézier
+    pub fn bezier(
+        control_points: [DVec3; 4],
+        widths: [f64; 2],
+        mode: CurveMode,
+        material: Material,
+    ) -> Curve {
+        Curve {
+            control_points,
+            widths,
+            mode,
+            material,
+        }
+    }
+
+    /// Construtor de um segmento de B-spline cúbica uniforme, convertido pra forma de Bézier
+    pub fn b_spline(
+        [p0, p1, p2, p3]: [DVec3; 4],
+        widths: [f64; 2],
+        mode: CurveMode,
+        material: Material,
+    ) -> Curve {
+        let control_points = [
+            (p0 + 4.0 * p1 + p2) / 6.0,
+            (2.0 * p1 + p2) / 3.0,
+            (p1 + 2.0 * p2) / 3.0,
+            (p1 + 4.0 * p2 + p3) / 6.0,
+        ];
+        Curve::bezier(control_points, widths, mode, material)
+    }
+
+    /// Constrói um fio inteiro (B-spline cúbica uniforme com os pontos de controle dados)
+    /// como uma curva por segmento, com a largura variando de `widths[0]` até `widths[1]`
+    /// ao longo do fio. Um fio com menos de 4 pontos não tem nenhum segmento.
+    pub fn strand(
+        points: &[DVec3],
+        widths: [f64; 2],
+        mode: CurveMode,
+        material: Material,
+    ) -> Vec<Curve> {
+        let segments = points.len().saturating_sub(3);
+        let width_at = |i: usize| {
+            let s = i as f64 / segments as f64;
+            widths[0] + (widths[1] - widths[0]) * s
+        };
+        points
+            .windows(4)
+            .enumerate()
+            .map(|(i, window)| {
+                let window = [window[0], window[1], window[2], window[3]];
+                Curve::b_spline(window, [width_at(i), width_at(i + 1)], mode, material)
+            })
+            .collect()
+    }
+
+    /// Ponto da curva no parâmetro u (entre 0 e 1)
+    pub fn point_at(&self, u: f64) -> DVec3 {
+        bezier_point(self.control_points, u)
+    }
+
+    /// Tangente unitária da curva no parâmetro u
+    pub fn tangent_at(&self, u: f64) -> DVec3 {
+        let (_, derivatives) = bernstein(u);
+        let tangent = self
+            .control_points
+            .iter()
+            .zip(derivatives)
+            .map(|(&p, b)| p * b)
+            .sum::<DVec3>();
+        // Nas pontas, a derivada some se um ponto de controle coincide com a ponta
+        tangent
+            .try_normalize()
+            .unwrap_or_else(|| (self.control_points[3] - self.control_points[0]).normalize())
+    }
+
+    /// Largura da curva no parâmetro u
+    pub fn width_at(&self, u: f64) -> f64 {
+        self.widths[0] + (self.widths[1] - self.widths[0]) * u
+    }
+
+    /// Caixa alinhada aos eixos que envolve a curva: a dos pontos de controle,
+    /// aumentada da metade da maior largura
+    pub fn aabb(&self) -> Aabb {
+        let max_width = self.widths[0].max(self.widths[1]);
+        Aabb::from_points(self.control_points).padded(max_width / 2.0)
+    }
+
+    /// Retorna a interseção mais próxima de um raio com a curva (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        // Leva a curva pro sistema de coordenadas do raio: p0 na origem e dr no eixo z.
+        // Lá, o raio atravessa a curva se ela passa a menos de meia largura da origem no plano xy.
+        let dr_length = ray.dr.length();
+        let z_axis = ray.dr / dr_length;
+        let (x_axis, y_axis) = z_axis.any_orthonormal_pair();
+        let to_ray_space = |p: DVec3| {
+            let p = p - ray.p0;
+            DVec3::new(p.dot(x_axis), p.dot(y_axis), p.dot(z_axis))
+        };
+        let points = self.control_points.map(to_ray_space);
+
+        // Número de subdivisões necessárias pra que cada pedaço da curva seja
+        // praticamente um segmento de reta (em relação à largura)
+        let max_width = self.widths[0].max(self.widths[1]);
+        let curvature = (0..2)
+            .map(|i| (points[i] - 2.0 * points[i + 1] + points[i + 2]).length())
+            .fold(0.0, f64::max);
+        let epsilon = max_width * 0.05;
+        let depth = if curvature > 0.0 && epsilon > 0.0 {
+            let r0 = (std::f64::consts::SQRT_2 * 6.0 * curvature / (8.0 * epsilon)).log2() / 2.0;
+            (r0.round().max(0.0) as usize).min(MAX_DEPTH)
+        } else {
+            0
+        };
+
+        // No sistema do raio, z = t * |dr|, então o intervalo do raio vira um intervalo em z
+        let z_range = [ray.t_min * dr_length, ray.t_max * dr_length];
+        let mut closest = None;
+        self.segment_intersects(points, [0.0, 1.0], depth, z_range, &mut closest);
+        let (z, u) = closest?;
+
+        let t = z / dr_length;
+        let p = ray.at(t);
+        let tangent = self.tangent_at(u);
+        let normal = match self.mode {
+            // A fita fica virada pro raio: a normal aponta pra ele, perpendicular à tangente
+            CurveMode::Ribbon => (-z_axis).reject_from_normalized(tangent),
+            // No tubo, a normal sai do centro da seção em direção ao ponto
+            CurveMode::Tube => (p - self.point_at(u)).reject_from_normalized(tangent),
+        }
+        .try_normalize()
+        .unwrap_or(-z_axis);
+
+        Some(Intersection {
+            t,
+            p,
+            normal,
+            geometric_normal: normal,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        })
+    }
+
+    /// Testa a interseção do raio com o pedaço da curva entre os parâmetros `u[0]` e `u[1]`
+    /// (com os pontos de controle no sistema de coordenadas do raio), guardando em `closest`
+    /// o z e o u da interseção mais próxima com z em `z_range`. Divide o pedaço ao meio até a profundidade
+    /// chegar a 0, e aí trata ele como um segmento de reta.
+    fn segment_intersects(
+        &self,
+        points: [DVec3; 4],
+        u: [f64; 2],
+        depth: usize,
+        z_range: [f64; 2],
+        closest: &mut Option<(f64, f64)>,
+    ) {
+        // Descarta o pedaço se a caixa que envolve ele (engordada pela largura)
+        // não contém a origem do plano xy, ou está fora do intervalo do raio ou atrás da
+        // interseção atual
+        let half_width = self.width_at(u[0]).max(self.width_at(u[1])) / 2.0;
+        let min = points.iter().fold(DVec3::INFINITY, |min, &p| min.min(p)) - half_width;
+        let max = points
+            .iter()
+            .fold(DVec3::NEG_INFINITY, |max, &p| max.max(p))
+            + half_width;
+        let z_limit = closest.map_or(z_range[1], |(z, _)| z);
+        if min.x > 0.0
+            || max.x < 0.0
+            || min.y > 0.0
+            || max.y < 0.0
+            || max.z < z_range[0]
+            || min.z > z_limit
+        {
+            return;
+        }
+
+        if depth > 0 {
+            let u_mid = (u[0] + u[1]) / 2.0;
+            let (left, right) = split_curve(points);
+            self.segment_intersects(left, [u[0], u_mid], depth - 1, z_range, closest);
+            self.segment_intersects(right, [u_mid, u[1]], depth - 1, z_range, closest);
+            return;
+        }
+
+        // A origem tem que estar entre as retas perpendiculares à curva nas pontas
+        // do pedaço, pra que os pedaços vizinhos não colidam duas vezes no mesmo lugar
+        let [p0, p1, p2, p3] = points.map(|p| p.truncate());
+        if (p1 - p0).dot(-p0) < 0.0 || (p2 - p3).dot(-p3) < 0.0 {
+            return;
+        }
+
+        // Ponto do segmento p0-p3 mais próximo da origem no plano xy, refinado por
+        // alguns passos de Gauss-Newton pro ponto do pedaço de curva mais próximo dela
+        let segment = p3 - p0;
+        let mut w = if segment.length_squared() > 0.0 {
+            ((-p0).dot(segment) / segment.length_squared()).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        for _ in 0..3 {
+            let (basis, derivatives) = bernstein(w);
+            let point: DVec2 = points
+                .iter()
+                .zip(basis)
+                .map(|(p, b)| p.truncate() * b)
+                .sum();
+            let derivative: DVec2 = points
+                .iter()
+                .zip(derivatives)
+                .map(|(p, b)| p.truncate() * b)
+                .sum();
+            if derivative.length_squared() > 0.0 {
+                w = (w - point.dot(derivative) / derivative.length_squared()).clamp(0.0, 1.0);
+            }
+        }
+        let curve_u = u[0] + (u[1] - u[0]) * w;
+        let half_width = self.width_at(curve_u) / 2.0;
+        let center = bezier_point(points, w);
+        let distance = center.truncate().length();
+        if distance > half_width {
+            return;
+        }
+
+        let z = match self.mode {
+            CurveMode::Ribbon => center.z,
+            // No tubo, o raio bate na superfície antes de chegar no plano do centro
+            CurveMode::Tube => {
+                center.z
+                    - (half_width * half_width - distance * distance)
+                        .max(0.0)
+                        .sqrt()
+            }
+        };
+        if z > z_range[0] && z < z_limit {
+            *closest = Some((z, curve_u));
+        }
+    }
+}
+
+impl Shape for Curve {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
+
+/// Ponto de uma curva de Bézier cúbica no parâmetro u
+fn bezier_point(points: [DVec3; 4], u: f64) -> DVec3 {
+    let (basis, _) = bernstein(u);
+    points.iter().zip(basis).map(|(&p, b)| p * b).sum()
+}
@@ -0,0 +1,105 @@
+use glam::DVec3;
+
+use crate::{
+    Aabb, Intersection, Lanes, Lanes3, Material, Ray, RayPacket,
+    shapes::{
+        Shape,
+        plane::{plane_intersects_packet, plane_line_intersects},
+    },
+};
+
+/// Disco definido pelo seu centro, sua normal e seu raio
+pub struct Disk {
+    /// Centro do disco
+    pub center: DVec3,
+    /// Vetor normal ao disco
+    pub normal: DVec3,
+    /// Raio do disco
+    pub radius: f64,
+    /// Material do disco
+    pub material: Material,
+}
+
+impl Disk {
+    /// Construtor do disco
+    pub fn new(center: DVec3, normal: DVec3, radius: f64, material: Material) -> Disk {
+        Disk {
+            center,
+            normal: normal.normalize(),
+            radius,
+            material,
+        }
+    }
+
+    /// Caixa alinhada aos eixos que envolve o disco
+    pub fn aabb(&self) -> Aabb {
+        disk_aabb(self.center, self.normal, self.radius)
+    }
+
+    /// Retorna a interseção de um raio com o disco (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let t = disk_intersects(ray, self.center, self.normal, self.radius)?;
+        Some(Intersection {
+            t,
+            p: ray.at(t),
+            normal: self.normal,
+            geometric_normal: self.normal,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        })
+    }
+}
+
+impl Shape for Disk {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
+
+/// Teste de interseção raio-disco: interseção raio-plano, e o ponto de interseção deve
+/// estar a uma distância de no máximo `radius` do centro (`None` se não há interseção
+/// dentro do intervalo do raio)
+pub(crate) fn disk_intersects(ray: &Ray, center: DVec3, normal: DVec3, radius: f64) -> Option<f64> {
+    disk_line_intersects(ray, center, normal, radius).filter(|&t| ray.contains(t))
+}
+
+/// `disk_intersects` feito em todos os raios de um pacote ao mesmo tempo
+/// (infinito nos raios que não acertam o disco)
+pub(crate) fn disk_intersects_packet(
+    packet: &RayPacket,
+    center: DVec3,
+    normal: DVec3,
+    radius: f64,
+) -> Lanes {
+    let t = plane_intersects_packet(packet, center, normal);
+    let distance2 = (packet.at(t) - Lanes3::splat(center)).length_squared();
+    // Nos raios sem interseção com o plano, a distância é NaN e a comparação é falsa
+    Lanes::select(distance2.0.map(|d| d <= radius * radius), t, Lanes::INFINITY)
+}
+
+/// Teste de interseção da reta do raio com o disco (t de qualquer sinal)
+pub(crate) fn disk_line_intersects(
+    ray: &Ray,
+    center: DVec3,
+    normal: DVec3,
+    radius: f64,
+) -> Option<f64> {
+    let t = plane_line_intersects(ray, center, normal)?;
+    if (ray.at(t) - center).length_squared() > radius * radius {
+        return None;
+    }
+    Some(t)
+}
+
+/// Caixa alinhada aos eixos que envolve um disco. Num eixo de direção e, o disco
+/// vai de -r*sqrt(1 - (n•e)²) até +r*sqrt(1 - (n•e)²) a partir do centro.
+pub(crate) fn disk_aabb(center: DVec3, normal: DVec3, radius: f64) -> Aabb {
+    let normal = normal.normalize();
+    let extent = (DVec3::ONE - normal * normal).map(|x| x.max(0.0).sqrt());
+    Aabb::from_center(center, radius * extent)
+}
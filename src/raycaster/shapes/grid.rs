@@ -0,0 +1,134 @@
+use glam::DVec3;
+
+use crate::{Aabb, Ray};
+
+/// Percurso de um raio pelas células de uma grade regular alinhada aos eixos, em ordem,
+/// pelo algoritmo de Amanatides-Woo (DDA 3D): o raio sempre avança pra célula vizinha
+/// no eixo cuja próxima borda de célula ele cruza primeiro
+pub(crate) struct GridTraversal {
+    cell: [usize; 3],
+    step: [isize; 3],
+    t_next: [f64; 3],
+    t_delta: [f64; 3],
+    t: f64,
+    t_exit: f64,
+    axis: usize,
+    dimensions: [usize; 3],
+    finished: bool,
+}
+
+/// Célula da grade atravessada por um raio
+pub(crate) struct GridCell {
+    /// Posição da célula na grade
+    pub index: [usize; 3],
+    /// t em que o raio entra na célula
+    pub t_enter: f64,
+    /// t em que o raio sai da célula
+    pub t_exit: f64,
+    /// Eixo da face por onde o raio entra na célula
+    pub axis: usize,
+}
+
+impl GridTraversal {
+    /// Começa o percurso do raio (no intervalo dele) pela grade com a quina de menor
+    /// x, y e z em `origin` (None se o raio não passa pela grade)
+    pub(crate) fn new(
+        ray: &Ray,
+        origin: DVec3,
+        cell_size: DVec3,
+        dimensions: [usize; 3],
+    ) -> Option<GridTraversal> {
+        if dimensions.contains(&0) {
+            return None;
+        }
+        let max = origin
+            + cell_size
+                * DVec3::new(
+                    dimensions[0] as f64,
+                    dimensions[1] as f64,
+                    dimensions[2] as f64,
+                );
+
+        // Intervalo de t em que o raio está dentro da grade
+        let (t_enter, t_exit) = Aabb::new(origin, max).intersects(ray)?;
+        // Eixo por onde o raio entra: o do plano da caixa em que t_enter foi calculado
+        // (0 se o raio já começa dentro da grade)
+        let axis = (0..3)
+            .find(|&i| {
+                let near = if ray.dr[i] > 0.0 { origin[i] } else { max[i] };
+                ray.dr[i] != 0.0 && (near - ray.p0[i]) / ray.dr[i] == t_enter
+            })
+            .unwrap_or(0);
+
+        let local = (ray.at(t_enter) - origin) / cell_size;
+        let mut cell = [0; 3];
+        let mut step = [0; 3];
+        let mut t_next = [f64::INFINITY; 3];
+        let mut t_delta = [f64::INFINITY; 3];
+        for i in 0..3 {
+            cell[i] = (local[i].floor().max(0.0) as usize).min(dimensions[i] - 1);
+            let direction = ray.dr[i];
+            if direction > 0.0 {
+                step[i] = 1;
+                t_next[i] = t_enter + ((cell[i] + 1) as f64 - local[i]) * cell_size[i] / direction;
+            } else if direction < 0.0 {
+                step[i] = -1;
+                t_next[i] = t_enter + (cell[i] as f64 - local[i]) * cell_size[i] / direction;
+            }
+            if direction != 0.0 {
+                t_delta[i] = cell_size[i] / direction.abs();
+            }
+        }
+
+        Some(GridTraversal {
+            cell,
+            step,
+            t_next,
+            t_delta,
+            t: t_enter,
+            t_exit,
+            axis,
+            dimensions,
+            finished: false,
+        })
+    }
+}
+
+impl Iterator for GridTraversal {
+    type Item = GridCell;
+
+    fn next(&mut self) -> Option<GridCell> {
+        if self.finished {
+            return None;
+        }
+        let t_next = self.t_next;
+        let next_axis = if t_next[0] < t_next[1] {
+            if t_next[0] < t_next[2] { 0 } else { 2 }
+        } else if t_next[1] < t_next[2] {
+            1
+        } else {
+            2
+        };
+        let current = GridCell {
+            index: self.cell,
+            t_enter: self.t,
+            t_exit: t_next[next_axis].min(self.t_exit),
+            axis: self.axis,
+        };
+
+        // Prepara a próxima célula (ou termina, se o raio sai da grade)
+        let next = self.cell[next_axis] as isize + self.step[next_axis];
+        if t_next[next_axis] > self.t_exit
+            || next < 0
+            || next >= self.dimensions[next_axis] as isize
+        {
+            self.finished = true;
+        } else {
+            self.cell[next_axis] = next as usize;
+            self.t = t_next[next_axis];
+            self.t_next[next_axis] += self.t_delta[next_axis];
+            self.axis = next_axis;
+        }
+        Some(current)
+    }
+}
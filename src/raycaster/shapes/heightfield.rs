@@ -0,0 +1,248 @@
+use glam::{DVec2, DVec3};
+use raylib::prelude::Image;
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{
+        Shape,
+        triangle::{interpolate_normal, triangle_intersects},
+    },
+};
+
+/// Terreno definido por uma grade regular de alturas no plano xz. Cada célula da grade
+/// é dividida em 2 triângulos, com as normais interpoladas entre os vértices.
+pub struct Heightfield {
+    /// Quina da grade com menor x e menor z (a altura 0 é o y desse ponto)
+    pub origin: DVec3,
+    /// Tamanho de cada célula da grade nos eixos x e z
+    pub cell_size: DVec2,
+    /// Número de vértices da grade no eixo x
+    pub columns: usize,
+    /// Número de vértices da grade no eixo z
+    pub rows: usize,
+    /// Alturas dos vértices, linha por linha (índice `row * columns + column`)
+    pub heights: Vec<f64>,
+    /// Normais dos vértices, na mesma ordem das alturas
+    pub normals: Vec<DVec3>,
+    /// Material do terreno
+    pub material: Material,
+    /// Menor e maior altura da grade, usadas na caixa que envolve o terreno
+    min_height: f64,
+    max_height: f64,
+}
+
+impl Heightfield {
+    /// Construtor do terreno a partir das alturas dos vértices (linha por linha, com
+    /// `columns` alturas por linha) e do tamanho da grade nos eixos x e z.
+    /// As normais são calculadas por diferenças centrais entre as alturas.
+    pub fn new(
+        origin: DVec3,
+        size: DVec2,
+        columns: usize,
+        heights: Vec<f64>,
+        material: Material,
+    ) -> Heightfield {
+        assert!(columns >= 2, "a grade precisa de pelo menos 2 colunas");
+        assert!(
+            heights.len().is_multiple_of(columns) && heights.len() / columns >= 2,
+            "a grade precisa de pelo menos 2 linhas completas"
+        );
+        let rows = heights.len() / columns;
+        let cell_size = size / DVec2::new((columns - 1) as f64, (rows - 1) as f64);
+        let min_height = heights.iter().copied().fold(f64::INFINITY, f64::min);
+        let max_height = heights.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        let mut heightfield = Heightfield {
+            origin,
+            cell_size,
+            columns,
+            rows,
+            heights,
+            normals: Vec::new(),
+            material,
+            min_height,
+            max_height,
+        };
+        heightfield.normals = heightfield.vertex_normals();
+        heightfield
+    }
+
+    /// Constrói o terreno amostrando uma função y = f(x, z) (em coordenadas da cena)
+    /// numa grade de `columns` x `rows` vértices
+    pub fn from_fn(
+        origin: DVec3,
+        size: DVec2,
+        columns: usize,
+        rows: usize,
+        f: impl Fn(f64, f64) -> f64,
+        material: Material,
+    ) -> Heightfield {
+        let cell_size = size / DVec2::new((columns - 1) as f64, (rows - 1) as f64);
+        let heights = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let x = origin.x + column as f64 * cell_size.x;
+                let z = origin.z + row as f64 * cell_size.y;
+                f(x, z) - origin.y
+            })
+            .collect();
+        Heightfield::new(origin, size, columns, heights, material)
+    }
+
+    /// Constrói o terreno a partir de uma imagem em tons de cinza: cada pixel é um vértice,
+    /// com preto na altura 0 e branco na altura `size.y`. As colunas da imagem vão no
+    /// eixo x e as linhas no eixo z. Imagens coloridas usam a luminância dos pixels.
+    pub fn from_image(
+        image: &Image,
+        origin: DVec3,
+        size: DVec3,
+        material: Material,
+    ) -> Heightfield {
+        let heights = image
+            .get_image_data()
+            .iter()
+            .map(|color| {
+                let luminance =
+                    0.299 * color.r as f64 + 0.587 * color.g as f64 + 0.114 * color.b as f64;
+                luminance / 255.0 * size.y
+            })
+            .collect();
+        Heightfield::new(
+            origin,
+            DVec2::new(size.x, size.z),
+            image.width() as usize,
+            heights,
+            material,
+        )
+    }
+
+    /// Caixa alinhada aos eixos que envolve o terreno
+    pub fn aabb(&self) -> Aabb {
+        let min = self.origin + DVec3::new(0.0, self.min_height, 0.0);
+        let max = self.origin
+            + DVec3::new(
+                (self.columns - 1) as f64 * self.cell_size.x,
+                self.max_height,
+                (self.rows - 1) as f64 * self.cell_size.y,
+            );
+        Aabb::from_points([min, max])
+    }
+
+    /// Retorna a interseção mais próxima de um raio com o terreno (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let (t_enter, t_exit) = self.aabb().intersects(ray)?;
+
+        // Percorre as células que o raio atravessa no plano xz em ordem (DDA 2D):
+        // a primeira célula com interseção tem a interseção mais próxima
+        let cells = [self.columns - 1, self.rows - 1];
+        let p = ray.at(t_enter) - self.origin;
+        let local = [p.x / self.cell_size.x, p.z / self.cell_size.y];
+        let directions = [ray.dr.x, ray.dr.z];
+        let sizes = [self.cell_size.x, self.cell_size.y];
+
+        let mut cell = [0; 2];
+        let mut step = [0; 2];
+        let mut t_next = [f64::INFINITY; 2];
+        let mut t_delta = [f64::INFINITY; 2];
+        for axis in 0..2 {
+            cell[axis] = (local[axis].floor().max(0.0) as usize).min(cells[axis] - 1);
+            if directions[axis] > 0.0 {
+                step[axis] = 1;
+                t_next[axis] = t_enter
+                    + ((cell[axis] + 1) as f64 - local[axis]) * sizes[axis] / directions[axis];
+            } else if directions[axis] < 0.0 {
+                step[axis] = -1;
+                t_next[axis] =
+                    t_enter + (cell[axis] as f64 - local[axis]) * sizes[axis] / directions[axis];
+            }
+            if directions[axis] != 0.0 {
+                t_delta[axis] = sizes[axis] / directions[axis].abs();
+            }
+        }
+
+        loop {
+            if let Some(intersection) = self.cell_intersects(ray, cell[0], cell[1]) {
+                return Some(intersection);
+            }
+            // Avança pra célula vizinha no eixo cuja borda o raio cruza primeiro
+            let axis = if t_next[0] < t_next[1] { 0 } else { 1 };
+            if t_next[axis] > t_exit {
+                return None;
+            }
+            let next = cell[axis] as isize + step[axis];
+            if next < 0 || next >= cells[axis] as isize {
+                return None;
+            }
+            cell[axis] = next as usize;
+            t_next[axis] += t_delta[axis];
+        }
+    }
+
+    /// Posição do vértice da grade na coluna e linha dadas
+    fn vertex(&self, column: usize, row: usize) -> DVec3 {
+        self.origin
+            + DVec3::new(
+                column as f64 * self.cell_size.x,
+                self.heights[row * self.columns + column],
+                row as f64 * self.cell_size.y,
+            )
+    }
+
+    /// Testa a interseção do raio com os 2 triângulos de uma célula da grade
+    fn cell_intersects(&self, ray: &Ray, column: usize, row: usize) -> Option<Intersection<'_>> {
+        let corners = [
+            (column, row),
+            (column, row + 1),
+            (column + 1, row),
+            (column + 1, row + 1),
+        ];
+        [[0, 1, 2], [2, 1, 3]]
+            .into_iter()
+            .filter_map(|triangle| {
+                let [a, b, c] = triangle.map(|i| corners[i]);
+                let [va, vb, vc] = [a, b, c].map(|(column, row)| self.vertex(column, row));
+                let (t, barycentric) = triangle_intersects(ray, va, vb, vc)?;
+                let normals =
+                    [a, b, c].map(|(column, row)| self.normals[row * self.columns + column]);
+                Some((t, barycentric, normals, (vb - va).cross(vc - va).normalize()))
+            })
+            .min_by(|(t1, ..), (t2, ..)| t1.total_cmp(t2))
+            .map(|(t, barycentric, normals, face_normal)| Intersection {
+                t,
+                p: ray.at(t),
+                normal: interpolate_normal(normals, barycentric),
+                geometric_normal: face_normal,
+                material: self.material,
+                barycentric: Some(barycentric),
+                object: self,
+            })
+    }
+
+    /// Normais dos vértices: (-dh/dx, 1, -dh/dz) normalizado, com as derivadas
+    /// calculadas por diferenças centrais (ou laterais, nas bordas da grade)
+    fn vertex_normals(&self) -> Vec<DVec3> {
+        let height = |column: usize, row: usize| self.heights[row * self.columns + column];
+        (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let (left, right) = (column.saturating_sub(1), (column + 1).min(self.columns - 1));
+                let (back, front) = (row.saturating_sub(1), (row + 1).min(self.rows - 1));
+                let dh_dx = (height(right, row) - height(left, row))
+                    / ((right - left) as f64 * self.cell_size.x);
+                let dh_dz = (height(column, front) - height(column, back))
+                    / ((front - back) as f64 * self.cell_size.y);
+                DVec3::new(-dh_dx, 1.0, -dh_dz).normalize()
+            })
+            .collect()
+    }
+}
+
+impl Shape for Heightfield {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
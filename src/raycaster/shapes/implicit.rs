@@ -0,0 +1,103 @@
+use glam::DVec3;
+
+use crate::{Aabb, Intersection, Material, Ray, shapes::Shape, solvers::first_root};
+
+/// Superfície implícita f(p) = 0, com f negativa dentro do sólido e positiva fora, limitada
+/// a uma caixa alinhada aos eixos. Diferente do `Sdf`, f não precisa ser uma distância:
+/// basta saber o quanto ela pode variar, pela constante de Lipschitz (um limite para o
+/// tamanho do gradiente de f dentro da caixa). Uma constante pequena demais faz raios
+/// perderem partes da superfície; uma grande demais só deixa a renderização mais lenta.
+pub struct Implicit {
+    /// Campo escalar que define a superfície
+    pub field: Box<dyn Fn(DVec3) -> f64 + Send + Sync>,
+    /// Quina da caixa com menor x, y e z
+    pub min: DVec3,
+    /// Quina da caixa com maior x, y e z
+    pub max: DVec3,
+    /// Limite para o tamanho do gradiente de f dentro da caixa
+    pub lipschitz: f64,
+    /// Material da superfície
+    pub material: Material,
+    /// Precisão da busca pela superfície (e passo das diferenças finitas da normal)
+    pub epsilon: f64,
+}
+
+impl Implicit {
+    /// Construtor da superfície, com epsilon 1e-4
+    pub fn new(
+        field: impl Fn(DVec3) -> f64 + Send + Sync + 'static,
+        min: DVec3,
+        max: DVec3,
+        lipschitz: f64,
+        material: Material,
+    ) -> Implicit {
+        Implicit {
+            field: Box::new(field),
+            min,
+            max,
+            lipschitz,
+            material,
+            epsilon: 1e-4,
+        }
+    }
+
+    /// Caixa alinhada aos eixos que limita a superfície
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.min, self.max)
+    }
+
+    /// Retorna a interseção mais próxima de um raio com a superfície (None se não há interseção).
+    /// A raiz de f é procurada no trecho do raio dentro da caixa, dividindo esse trecho e
+    /// descartando os pedaços em que f não pode chegar a zero: num pedaço de comprimento L,
+    /// f fica a no máximo `lipschitz * L / 2` do valor dela no meio do pedaço.
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let dr_length = ray.dr.length();
+        let (t_enter, t_exit) = self.aabb().intersects(ray)?;
+
+        let t = first_root(
+            |t| (self.field)(ray.at(t)),
+            |a, b| {
+                let center = (self.field)(ray.at((a + b) / 2.0));
+                let radius = self.lipschitz * (b - a) / 2.0 * dr_length;
+                (center - radius, center + radius)
+            },
+            [t_enter, t_exit],
+            self.epsilon / dr_length,
+        )?;
+
+        let p = ray.at(t);
+        let normal = self.normal_at(p);
+        Some(Intersection {
+            t,
+            p,
+            normal,
+            geometric_normal: normal,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        })
+    }
+
+    /// Normal da superfície em p: o gradiente de f (calculado por diferenças centrais), normalizado
+    pub fn normal_at(&self, p: DVec3) -> DVec3 {
+        let h = self.epsilon;
+        let f = &self.field;
+        DVec3::new(
+            f(p + DVec3::X * h) - f(p - DVec3::X * h),
+            f(p + DVec3::Y * h) - f(p - DVec3::Y * h),
+            f(p + DVec3::Z * h) - f(p - DVec3::Z * h),
+        )
+        .try_normalize()
+        .unwrap_or(DVec3::Y)
+    }
+}
+
+impl Shape for Implicit {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
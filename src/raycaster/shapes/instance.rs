@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use glam::{DMat3, DMat4, DVec3};
+
+use crate::{
+    Aabb, Intersection, Ray,
+    shapes::{Shape, Span},
+    transforms::{
+        normal_matrix, rotation_matrix_from_axis_angle, scaling_matrix, translation_matrix,
+    },
+};
+
+/// Cópia de uma forma compartilhada, posicionada na cena por uma transformação afim.
+/// A mesma forma pode ser usada por várias instâncias sem ser duplicada.
+pub struct Instance {
+    /// Forma original, no seu próprio sistema de coordenadas
+    pub shape: Arc<dyn Shape>,
+    /// Transformação do sistema de coordenadas da forma para o da cena
+    transform: DMat4,
+    /// Transformação inversa (da cena para a forma)
+    inverse: DMat4,
+    /// Matriz que leva as normais da forma para a cena
+    normal_matrix: DMat3,
+}
+
+impl Instance {
+    /// Construtor da instância. A transformação deve ser inversível.
+    pub fn new(shape: Arc<dyn Shape>, transform: DMat4) -> Instance {
+        Instance {
+            shape,
+            transform,
+            inverse: transform.inverse(),
+            normal_matrix: normal_matrix(transform),
+        }
+    }
+
+    /// Transformação do sistema de coordenadas da forma para o da cena
+    pub fn transform(&self) -> DMat4 {
+        self.transform
+    }
+
+    /// Troca a transformação da instância
+    pub fn set_transform(&mut self, transform: DMat4) {
+        *self = Instance::new(self.shape.clone(), transform);
+    }
+
+    /// Retorna a instância movida por `offset`, depois da transformação atual
+    pub fn translated(self, offset: DVec3) -> Instance {
+        let transform = translation_matrix(offset) * self.transform;
+        Instance::new(self.shape, transform)
+    }
+
+    /// Retorna a instância girada ao redor de um eixo que passa pela origem,
+    /// depois da transformação atual
+    pub fn rotated(self, axis: DVec3, angle: f64) -> Instance {
+        let transform = rotation_matrix_from_axis_angle(axis, angle) * self.transform;
+        Instance::new(self.shape, transform)
+    }
+
+    /// Retorna a instância escalada em cada eixo (em relação à origem),
+    /// depois da transformação atual
+    pub fn scaled(self, factors: DVec3) -> Instance {
+        let transform = scaling_matrix(factors) * self.transform;
+        Instance::new(self.shape, transform)
+    }
+
+    /// Caixa alinhada aos eixos que envolve a forma transformada
+    pub fn aabb(&self) -> Aabb {
+        self.shape.aabb().transformed(self.transform)
+    }
+
+    /// Retorna a interseção mais próxima de um raio com a forma transformada
+    /// (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        // O t do raio levado pro sistema da forma é o mesmo do raio original,
+        // já que a direção não é normalizada
+        let intersection = self.shape.intersects(&self.to_local(ray))?;
+        Some(self.to_world(ray, intersection))
+    }
+
+    /// Diz se o raio acerta a forma transformada dentro do seu intervalo
+    pub fn occludes(&self, ray: &Ray) -> bool {
+        self.shape.occludes(&self.to_local(ray))
+    }
+
+    /// Retorna os intervalos do raio que estão dentro da forma transformada
+    pub fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.shape
+            .spans(&self.to_local(ray))
+            .into_iter()
+            .map(|span| Span {
+                entry: self.to_world(ray, span.entry),
+                exit: self.to_world(ray, span.exit),
+            })
+            .collect()
+    }
+
+    /// Leva um raio da cena para o sistema de coordenadas da forma
+    fn to_local(&self, ray: &Ray) -> Ray {
+        Ray::new(
+            self.inverse.transform_point3(ray.p0),
+            self.inverse.transform_vector3(ray.dr),
+        )
+        .with_interval(ray.t_min, ray.t_max)
+    }
+
+    /// Leva uma interseção com o raio local de volta para a cena
+    fn to_world<'a>(&self, ray: &Ray, intersection: Intersection<'a>) -> Intersection<'a> {
+        Intersection {
+            p: ray.at(intersection.t),
+            normal: (self.normal_matrix * intersection.normal).normalize(),
+            geometric_normal: (self.normal_matrix * intersection.geometric_normal).normalize(),
+            ..intersection
+        }
+    }
+}
+
+impl Shape for Instance {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn occludes(&self, ray: &Ray) -> bool {
+        self.occludes(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.spans(ray)
+    }
+}
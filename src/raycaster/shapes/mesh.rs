@@ -0,0 +1,181 @@
+use glam::DVec3;
+
+use crate::{
+    Aabb, Bvh, Intersection, Material, Ray,
+    shapes::{
+        Shape,
+        triangle::{interpolate_normal, triangle_intersects},
+    },
+};
+
+/// Malha de triângulos indexada, com uma normal por vértice (sombreamento suave)
+/// e, opcionalmente, uma cor por vértice. Os triângulos ficam numa BVH própria, então
+/// um raio só é testado contra os triângulos cujas caixas ele atravessa.
+pub struct TriangleMesh {
+    /// Posições dos vértices da malha
+    vertices: Vec<DVec3>,
+    /// Normais dos vértices (mesmo tamanho de `vertices`)
+    pub normals: Vec<DVec3>,
+    /// Cores dos vértices, de 0 a 1 (mesmo tamanho de `vertices`). Se presentes,
+    /// a cor interpolada no ponto substitui as cores ambiente e difusa do material.
+    pub colors: Option<Vec<DVec3>>,
+    /// Triângulos da malha, como índices em `vertices`
+    faces: Vec<[usize; 3]>,
+    /// Material da malha
+    pub material: Material,
+    /// Hierarquia de caixas dos triângulos (o objeto i é a face i). Os vértices e as faces
+    /// não podem mudar depois da construção, pra que ela continue valendo.
+    bvh: Bvh,
+}
+
+impl TriangleMesh {
+    /// Construtor da malha. As normais dos vértices são calculadas
+    /// a partir das normais das faces adjacentes.
+    pub fn new(vertices: Vec<DVec3>, faces: Vec<[usize; 3]>, material: Material) -> TriangleMesh {
+        let normals = vertex_normals(&vertices, &faces);
+        let bvh = face_bvh(&vertices, &faces);
+        TriangleMesh {
+            vertices,
+            normals,
+            colors: None,
+            faces,
+            material,
+            bvh,
+        }
+    }
+
+    /// Construtor da malha com normais dos vértices já conhecidas
+    pub fn with_normals(
+        vertices: Vec<DVec3>,
+        normals: Vec<DVec3>,
+        faces: Vec<[usize; 3]>,
+        material: Material,
+    ) -> TriangleMesh {
+        assert_eq!(
+            vertices.len(),
+            normals.len(),
+            "A malha precisa de exatamente uma normal por vértice"
+        );
+        let bvh = face_bvh(&vertices, &faces);
+        TriangleMesh {
+            vertices,
+            normals: normals.into_iter().map(|n| n.normalize()).collect(),
+            colors: None,
+            faces,
+            material,
+            bvh,
+        }
+    }
+
+    /// Adiciona uma cor a cada vértice da malha
+    pub fn with_colors(mut self, colors: Vec<DVec3>) -> TriangleMesh {
+        assert_eq!(
+            self.vertices.len(),
+            colors.len(),
+            "A malha precisa de exatamente uma cor por vértice"
+        );
+        self.colors = Some(colors);
+        self
+    }
+
+    /// Posições dos vértices da malha
+    pub fn vertices(&self) -> &[DVec3] {
+        &self.vertices
+    }
+
+    /// Triângulos da malha, como índices em `vertices`
+    pub fn faces(&self) -> &[[usize; 3]] {
+        &self.faces
+    }
+
+    /// Caixa alinhada aos eixos que envolve a malha
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.faces.iter().flatten().map(|&i| self.vertices[i]))
+    }
+
+    /// Retorna a interseção mais próxima de um raio com os triângulos da malha
+    /// (`None` se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.bvh
+            .closest_hit(ray, |i| self.face_intersects(&self.faces[i], ray))
+    }
+
+    /// Interseção do raio com um dos triângulos da malha (`None` se não há)
+    fn face_intersects(&self, face: &[usize; 3], ray: &Ray) -> Option<Intersection<'_>> {
+        let [a, b, c] = face.map(|i| self.vertices[i]);
+        let (t, barycentric) = triangle_intersects(ray, a, b, c)?;
+
+        let material = match &self.colors {
+            Some(colors) => {
+                let [a, b, c] = face.map(|i| colors[i]);
+                let color = a * barycentric.x + b * barycentric.y + c * barycentric.z;
+                Material {
+                    k_amb: color,
+                    k_dif: color,
+                    ..self.material
+                }
+            }
+            None => self.material,
+        };
+
+        Some(Intersection {
+            t,
+            p: ray.at(t),
+            normal: interpolate_normal(face.map(|i| self.normals[i]), barycentric),
+            geometric_normal: (b - a).cross(c - a).normalize(),
+            material,
+            barycentric: Some(barycentric),
+            object: self,
+        })
+    }
+
+    /// Diz se o raio acerta algum triângulo da malha dentro do seu intervalo,
+    /// parando no primeiro triângulo encontrado
+    pub fn occludes(&self, ray: &Ray) -> bool {
+        self.bvh.any_hit(ray, |i| {
+            let [a, b, c] = self.faces[i].map(|i| self.vertices[i]);
+            triangle_intersects(ray, a, b, c).is_some()
+        })
+    }
+}
+
+impl Shape for TriangleMesh {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn occludes(&self, ray: &Ray) -> bool {
+        self.occludes(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
+
+/// Constrói a BVH das caixas dos triângulos da malha
+fn face_bvh(vertices: &[DVec3], faces: &[[usize; 3]]) -> Bvh {
+    let bounds: Vec<_> = faces
+        .iter()
+        .map(|face| Aabb::from_points(face.map(|i| vertices[i])))
+        .collect();
+    Bvh::new(&bounds)
+}
+
+/// Calcula a normal de cada vértice como a média das normais das faces adjacentes,
+/// ponderada pela área de cada face
+pub(crate) fn vertex_normals(vertices: &[DVec3], faces: &[[usize; 3]]) -> Vec<DVec3> {
+    let mut normals = vec![DVec3::ZERO; vertices.len()];
+    for face in faces {
+        let [a, b, c] = face.map(|i| vertices[i]);
+        // O tamanho do produto vetorial é o dobro da área da face
+        let face_normal = (b - a).cross(c - a);
+        for &i in face {
+            normals[i] += face_normal;
+        }
+    }
+    normals
+        .into_iter()
+        .map(|n| n.try_normalize().unwrap_or(DVec3::Y))
+        .collect()
+}
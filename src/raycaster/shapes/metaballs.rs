@@ -0,0 +1,190 @@
+use glam::DVec3;
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::Shape,
+    solvers::{first_root, solve_quadratic},
+};
+
+/// Uma bola de um `Metaballs`: uma fonte de campo que vale `weight` no centro e cai suavemente
+/// até zero na distância `radius`. Pesos negativos "cavam" as bolas vizinhas.
+#[derive(Clone, Copy, Debug)]
+pub struct Metaball {
+    /// Centro da bola
+    pub center: DVec3,
+    /// Raio de influência da bola
+    pub radius: f64,
+    /// Peso (intensidade do campo no centro)
+    pub weight: f64,
+}
+
+/// Superfície "bolhosa" formada pela soma dos campos de várias bolas: é onde a soma
+/// vale `threshold`, com o lado de dentro sendo onde ela é maior. Cada bola contribui
+/// com `weight * (1 - d²/radius²)³` na distância d < radius do seu centro (e 0 fora),
+/// então bolas próximas se fundem suavemente, como gotas de líquido.
+pub struct Metaballs {
+    /// Bolas que geram o campo
+    pub balls: Vec<Metaball>,
+    /// Valor do campo na superfície
+    pub threshold: f64,
+    /// Material da superfície
+    pub material: Material,
+}
+
+impl Metaball {
+    /// Construtor da bola
+    pub fn new(center: DVec3, radius: f64, weight: f64) -> Metaball {
+        Metaball {
+            center,
+            radius,
+            weight,
+        }
+    }
+
+    /// Contribuição da bola para o campo em p
+    pub fn field(&self, p: DVec3) -> f64 {
+        self.weight * kernel((p - self.center).length_squared() / (self.radius * self.radius))
+    }
+
+    /// Gradiente da contribuição da bola para o campo em p
+    pub fn gradient(&self, p: DVec3) -> DVec3 {
+        let r2 = self.radius * self.radius;
+        let x = (p - self.center).length_squared() / r2;
+        if x >= 1.0 {
+            return DVec3::ZERO;
+        }
+        // d/dp (1 - |p - c|²/R²)³ = 3 (1 - x)² * (-2 (p - c) / R²)
+        self.weight * 3.0 * (1.0 - x) * (1.0 - x) * (-2.0 * (p - self.center) / r2)
+    }
+
+    /// Limites inferior e superior da contribuição da bola no pedaço `[a, b]` do raio
+    fn bounds(&self, ray: &Ray, a: f64, b: f64) -> (f64, f64) {
+        // O quadrado da distância até o centro é uma parábola em t: o mínimo é no ponto
+        // mais próximo do centro (dentro do pedaço), e o máximo numa das pontas
+        let closest = (self.center - ray.p0).dot(ray.dr) / ray.dr.length_squared();
+        let distance2 = |t: f64| (ray.at(t) - self.center).length_squared();
+        let r2 = self.radius * self.radius;
+        let k_max = kernel(distance2(closest.clamp(a, b)) / r2);
+        let k_min = kernel(distance2(a).max(distance2(b)) / r2);
+        if self.weight >= 0.0 {
+            (self.weight * k_min, self.weight * k_max)
+        } else {
+            (self.weight * k_max, self.weight * k_min)
+        }
+    }
+}
+
+impl Metaballs {
+    /// Construtor da superfície
+    pub fn new(balls: Vec<Metaball>, threshold: f64, material: Material) -> Metaballs {
+        Metaballs {
+            balls,
+            threshold,
+            material,
+        }
+    }
+
+    /// Valor do campo em p (a soma das contribuições das bolas)
+    pub fn field(&self, p: DVec3) -> f64 {
+        self.balls.iter().map(|ball| ball.field(p)).sum()
+    }
+
+    /// Gradiente do campo em p
+    pub fn gradient(&self, p: DVec3) -> DVec3 {
+        self.balls.iter().map(|ball| ball.gradient(p)).sum()
+    }
+
+    /// Caixa alinhada aos eixos que envolve as esferas de influência das bolas. Com
+    /// `threshold` positivo, a superfície só passa por onde alguma bola de peso positivo
+    /// contribui, então as bolas de peso negativo ficam de fora.
+    pub fn aabb(&self) -> Aabb {
+        self.balls
+            .iter()
+            .filter(|ball| ball.weight > 0.0 || self.threshold <= 0.0)
+            .map(|ball| Aabb::from_center(ball.center, DVec3::splat(ball.radius)))
+            .fold(Aabb::EMPTY, |aabb, ball| aabb.union(&ball))
+    }
+
+    /// Retorna a interseção mais próxima de um raio com a superfície (None se não há interseção).
+    /// A raiz de `campo - threshold` é procurada só no trecho do raio dentro das esferas de
+    /// influência, dividindo esse trecho e descartando os pedaços em que os limites do campo
+    /// (calculados bola a bola) mostram que ele não passa por `threshold`.
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let dr_length = ray.dr.length();
+        let min_radius = self
+            .balls
+            .iter()
+            .map(|ball| ball.radius)
+            .fold(f64::INFINITY, f64::min);
+
+        // Trecho do raio que passa pelas esferas de influência
+        let (t_start, t_end) = self
+            .balls
+            .iter()
+            .filter_map(|ball| {
+                let oc = ray.p0 - ball.center;
+                let [t1, t2] = solve_quadratic(
+                    ray.dr.length_squared(),
+                    2.0 * oc.dot(ray.dr),
+                    oc.length_squared() - ball.radius * ball.radius,
+                )?;
+                (t2 > ray.t_min && t1 < ray.t_max)
+                    .then_some((t1.max(ray.t_min), t2.min(ray.t_max)))
+            })
+            .fold(
+                (f64::INFINITY, f64::NEG_INFINITY),
+                |(start, end), (t1, t2)| (start.min(t1), end.max(t2)),
+            );
+        if t_start >= t_end {
+            return None;
+        }
+
+        let t = first_root(
+            |t| self.field(ray.at(t)) - self.threshold,
+            |a, b| {
+                let (low, high) = self
+                    .balls
+                    .iter()
+                    .map(|ball| ball.bounds(ray, a, b))
+                    .fold((0.0, 0.0), |(low, high), (l, h)| (low + l, high + h));
+                (low - self.threshold, high - self.threshold)
+            },
+            [t_start, t_end],
+            1e-3 * min_radius / dr_length,
+        )?;
+
+        // O campo cresce pra dentro, então a normal aponta contra o gradiente
+        let p = ray.at(t);
+        let normal = (-self.gradient(p))
+            .try_normalize()
+            .unwrap_or(-ray.dr.normalize());
+        Some(Intersection {
+            t,
+            p,
+            normal,
+            geometric_normal: normal,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        })
+    }
+}
+
+impl Shape for Metaballs {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
+
+/// Função de queda do campo, com x = d²/R²: (1 - x)³ para x < 1, e 0 fora
+fn kernel(x: f64) -> f64 {
+    if x >= 1.0 {
+        0.0
+    } else {
+        (1.0 - x) * (1.0 - x) * (1.0 - x)
+    }
+}
@@ -1,17 +1,97 @@
-use crate::{Intersection, Ray};
+use std::array;
+
+use crate::{Aabb, Intersection, Lanes, Ray, RayPacket};
+mod bezier;
+mod capsule;
 mod cilinder;
 mod cone;
+mod csg;
+mod cuboid;
+mod curve;
+mod disk;
+mod grid;
+mod heightfield;
+mod implicit;
+mod instance;
+mod mesh;
+mod metaballs;
+mod parametric;
 mod plane;
+mod point_cloud;
+mod polygon;
+mod quad;
+mod quadric;
+mod rounded_cilinder;
+mod sdf;
 mod sphere;
+mod torus;
+mod triangle;
+mod voxel_grid;
 
+pub use bezier::BezierPatch;
+pub use capsule::Capsule;
 pub use cilinder::Cilinder;
 pub use cone::Cone;
+pub use csg::{Csg, CsgOperation, Span};
+pub use cuboid::Cuboid;
+pub use curve::{Curve, CurveMode};
+pub use disk::Disk;
+pub use heightfield::Heightfield;
+pub use implicit::Implicit;
+pub use instance::Instance;
+pub use mesh::TriangleMesh;
+pub use metaballs::{Metaball, Metaballs};
+pub use parametric::ParametricSurface;
 pub use plane::Plane;
+pub use point_cloud::PointCloud;
+pub use polygon::Polygon;
+pub use quad::Quad;
+pub use quadric::{Quadric, QuadricClip};
+pub use rounded_cilinder::RoundedCilinder;
+pub use sdf::Sdf;
 pub use sphere::Sphere;
+pub use torus::Torus;
+pub use triangle::Triangle;
+pub use voxel_grid::VoxelGrid;
 
 /// """Classe""" 'objeto' com método que diz se um raio o intersecta ou não
-pub trait Shape: Sync {
-    /// Retorna a interseção de um raio com o objeto de t positivo mais próxima
-    /// (`None` se não há colisão).
-    fn intersects(&self, ray: &Ray) -> Option<Intersection>;
+pub trait Shape: Send + Sync {
+    /// Retorna a interseção de um raio com o objeto de t mais próximo dentro do
+    /// intervalo `t_min < t < t_max` do raio (`None` se não há colisão).
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>>;
+
+    /// Diz se o raio acerta o objeto em algum t do seu intervalo. É a consulta dos raios
+    /// de sombra, que não precisam da interseção mais próxima: formas com muitas partes
+    /// podem parar na primeira que o raio acerta.
+    fn occludes(&self, ray: &Ray) -> bool {
+        self.intersects(ray).is_some()
+    }
+
+    /// Retorna os t das interseções mais próximas de cada raio de um pacote com o objeto,
+    /// dentro dos intervalos dos raios (infinito nos raios sem interseção). Por padrão os
+    /// raios são testados um por um; as formas mais comuns testam o pacote inteiro de uma
+    /// vez, com SIMD.
+    fn intersects_packet(&self, packet: &RayPacket) -> Lanes {
+        Lanes(array::from_fn(|lane| {
+            if !packet.is_active(lane) {
+                return f64::INFINITY;
+            }
+            self.intersects(&packet.ray(lane))
+                .map_or(f64::INFINITY, |intersection| intersection.t)
+        }))
+    }
+
+    /// Retorna a caixa alinhada aos eixos que envolve o objeto, usada pela BVH da cena.
+    /// Formas ilimitadas (ou que não sabem os próprios limites) usam `Aabb::INFINITE`,
+    /// e são testadas por todos os raios.
+    fn aabb(&self) -> Aabb {
+        Aabb::INFINITE
+    }
+
+    /// Retorna todos os intervalos da reta do raio (inclusive os de t negativo, ignorando
+    /// o intervalo do raio) que estão dentro do sólido, em ordem crescente de t. É o que permite usar
+    /// a forma em um `Csg`. Formas que não delimitam um sólido não têm intervalos.
+    fn spans(&self, _ray: &Ray) -> Vec<Span<'_>> {
+        Vec::new()
+    }
 }
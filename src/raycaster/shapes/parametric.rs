@@ -0,0 +1,331 @@
+use glam::{DMat3, DVec3};
+
+use crate::{Aabb, Intersection, Material, Ray, shapes::Shape};
+
+/// Resolução padrão (em u e em v) da grade usada pra achar os pontos de partida do Newton
+const DEFAULT_RESOLUTION: usize = 32;
+/// Número máximo de iterações do método de Newton
+const NEWTON_ITERATIONS: usize = 16;
+
+/// Superfície paramétrica S(u, v) dada por uma função qualquer, com u e v num domínio
+/// retangular (como faixas de Möbius, garrafas de Klein e conchas).
+///
+/// O domínio é dividido numa grade de células. A caixa de cada célula é estimada a partir
+/// de alguns pontos da superfície (com uma folga pela curvatura), e as caixas são organizadas
+/// numa árvore. Nas células que o raio atravessa, a interseção é refinada pelo método de
+/// Newton, e as derivadas parciais (para o Newton e para a normal) são calculadas por
+/// diferenças finitas. Como a superfície pode ser aberta ou não orientável, a normal sempre
+/// aponta para o lado de onde o raio veio.
+pub struct ParametricSurface {
+    function: Box<dyn Fn(f64, f64) -> DVec3 + Send + Sync>,
+    u_range: [f64; 2],
+    v_range: [f64; 2],
+    /// Material da superfície
+    pub material: Material,
+    /// Árvore de caixas que envolvem pedaços da superfície, com as células da grade nas folhas
+    root: SurfaceNode,
+}
+
+/// Pedaço do domínio (u, v) da superfície, com uma caixa que envolve esse pedaço
+struct SurfaceNode {
+    bounds: Aabb,
+    u: [f64; 2],
+    v: [f64; 2],
+    children: Vec<SurfaceNode>,
+}
+
+impl ParametricSurface {
+    /// Construtor da superfície, com u em `u_range` e v em `v_range`, e uma grade
+    /// de 32 x 32 células
+    pub fn new(
+        function: impl Fn(f64, f64) -> DVec3 + Send + Sync + 'static,
+        u_range: [f64; 2],
+        v_range: [f64; 2],
+        material: Material,
+    ) -> ParametricSurface {
+        ParametricSurface::with_resolution(
+            function,
+            u_range,
+            v_range,
+            [DEFAULT_RESOLUTION; 2],
+            material,
+        )
+    }
+
+    /// Construtor da superfície com uma grade de `resolution[0]` x `resolution[1]` células
+    /// (em u e v). Superfícies com mais detalhes precisam de grades mais finas, pra que
+    /// cada célula seja praticamente plana.
+    pub fn with_resolution(
+        function: impl Fn(f64, f64) -> DVec3 + Send + Sync + 'static,
+        u_range: [f64; 2],
+        v_range: [f64; 2],
+        resolution: [usize; 2],
+        material: Material,
+    ) -> ParametricSurface {
+        let function = Box::new(function);
+        let u_range = [u_range[0].min(u_range[1]), u_range[0].max(u_range[1])];
+        let v_range = [v_range[0].min(v_range[1]), v_range[0].max(v_range[1])];
+        let resolution = resolution.map(|r| r.max(1));
+        let root = SurfaceNode::new(
+            &*function,
+            u_range,
+            v_range,
+            [0, resolution[0]],
+            [0, resolution[1]],
+            resolution,
+        );
+        ParametricSurface {
+            function,
+            u_range,
+            v_range,
+            material,
+            root,
+        }
+    }
+
+    /// Domínio de u
+    pub fn u_range(&self) -> [f64; 2] {
+        self.u_range
+    }
+
+    /// Domínio de v
+    pub fn v_range(&self) -> [f64; 2] {
+        self.v_range
+    }
+
+    /// Ponto da superfície nas coordenadas (u, v)
+    pub fn point_at(&self, u: f64, v: f64) -> DVec3 {
+        (self.function)(u, v)
+    }
+
+    /// Normal unitária da superfície nas coordenadas (u, v): Su x Sv normalizado.
+    /// Onde uma das derivadas se anula (como nos polos de uma esfera), a normal é
+    /// calculada num ponto um pouco mais pra dentro do domínio.
+    pub fn normal_at(&self, u: f64, v: f64) -> DVec3 {
+        let (su, sv) = self.derivatives(u, v);
+        su.cross(sv).try_normalize().unwrap_or_else(|| {
+            let u_mid = (self.u_range[0] + self.u_range[1]) / 2.0;
+            let v_mid = (self.v_range[0] + self.v_range[1]) / 2.0;
+            let (u, v) = (u + (u_mid - u) * 1e-4, v + (v_mid - v) * 1e-4);
+            let (su, sv) = self.derivatives(u, v);
+            su.cross(sv).try_normalize().unwrap_or(DVec3::Y)
+        })
+    }
+
+    /// Caixa alinhada aos eixos que envolve a superfície (a da raiz da árvore de caixas)
+    pub fn aabb(&self) -> Aabb {
+        self.root.bounds
+    }
+
+    /// Retorna a interseção mais próxima de um raio com a superfície (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let mut closest = None;
+        self.node_intersects(&self.root, ray, &mut closest);
+        closest.map(|(t, u, v)| {
+            let mut normal = self.normal_at(u, v);
+            if normal.dot(ray.dr) > 0.0 {
+                normal = -normal;
+            }
+            Intersection {
+                t,
+                p: ray.at(t),
+                normal,
+                geometric_normal: normal,
+                material: self.material,
+                barycentric: None,
+                object: self,
+            }
+        })
+    }
+
+    /// Procura interseções nos pedaços da superfície cujas caixas o raio atravessa antes
+    /// da interseção mais próxima encontrada até agora. Nas folhas da árvore, refina o
+    /// ponto pelo método de Newton, começando na entrada e no meio da caixa.
+    fn node_intersects(
+        &self,
+        node: &SurfaceNode,
+        ray: &Ray,
+        closest: &mut Option<(f64, f64, f64)>,
+    ) {
+        let Some((t_enter, t_exit)) = node.bounds.intersects(ray) else {
+            return;
+        };
+        if closest.is_some_and(|(t, ..)| t_enter > t) {
+            return;
+        }
+
+        if node.children.is_empty() {
+            let hit = [t_enter, (t_enter + t_exit) / 2.0]
+                .into_iter()
+                .find_map(|t_start| self.newton(node, ray, t_start));
+            if let Some((t, u, v)) = hit
+                && closest.is_none_or(|(closest_t, ..)| t < closest_t)
+            {
+                *closest = Some((t, u, v));
+            }
+            return;
+        }
+        for child in &node.children {
+            self.node_intersects(child, ray, closest);
+        }
+    }
+
+    /// Resolve S(u, v) = p0 + t*dr pelo método de Newton, começando no centro da célula
+    /// e em `t_start`. Só aceita soluções dentro do domínio.
+    fn newton(&self, node: &SurfaceNode, ray: &Ray, t_start: f64) -> Option<(f64, f64, f64)> {
+        let mut u = (node.u[0] + node.u[1]) / 2.0;
+        let mut v = (node.v[0] + node.v[1]) / 2.0;
+        let mut t = t_start;
+        let tolerance = 1e-9 * (self.root.bounds.max - self.root.bounds.min).length().max(1.0);
+
+        for _ in 0..NEWTON_ITERATIONS {
+            let error = self.point_at(u, v) - ray.at(t);
+            if error.length() < tolerance {
+                // A solução pode ter saído da célula (em raios quase tangentes), mas ainda
+                // é um ponto da superfície se está dentro do domínio
+                let (u_range, v_range) = (self.u_range, self.v_range);
+                let u_margin = 1e-9 * (u_range[1] - u_range[0]);
+                let v_margin = 1e-9 * (v_range[1] - v_range[0]);
+                let inside = (u_range[0] - u_margin..=u_range[1] + u_margin).contains(&u)
+                    && (v_range[0] - v_margin..=v_range[1] + v_margin).contains(&v);
+                return (inside && ray.contains(t)).then_some((t, u, v));
+            }
+            // Jacobiana de F(u, v, t) = S(u, v) - (p0 + t*dr)
+            let (su, sv) = self.derivatives(u, v);
+            let jacobian = DMat3::from_cols(su, sv, -ray.dr);
+            if jacobian.determinant().abs() < 1e-14 {
+                return None;
+            }
+            let step = jacobian.inverse() * error;
+            u -= step.x;
+            v -= step.y;
+            t -= step.z;
+        }
+        None
+    }
+
+    /// Derivadas parciais Su e Sv da superfície em (u, v), por diferenças centrais
+    fn derivatives(&self, u: f64, v: f64) -> (DVec3, DVec3) {
+        let hu = 1e-6 * (self.u_range[1] - self.u_range[0]).abs().max(1e-3);
+        let hv = 1e-6 * (self.v_range[1] - self.v_range[0]).abs().max(1e-3);
+        let su = (self.point_at(u + hu, v) - self.point_at(u - hu, v)) / (2.0 * hu);
+        let sv = (self.point_at(u, v + hv) - self.point_at(u, v - hv)) / (2.0 * hv);
+        (su, sv)
+    }
+}
+
+impl Shape for ParametricSurface {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
+
+impl SurfaceNode {
+    /// Constrói o nó com as células `columns[0]..columns[1]` (em u) e `rows[0]..rows[1]`
+    /// (em v) da grade, dividindo ao meio no eixo com mais células até sobrar uma só
+    fn new(
+        function: &dyn Fn(f64, f64) -> DVec3,
+        u_range: [f64; 2],
+        v_range: [f64; 2],
+        columns: [usize; 2],
+        rows: [usize; 2],
+        resolution: [usize; 2],
+    ) -> SurfaceNode {
+        let u_at = |column: usize| {
+            u_range[0] + (u_range[1] - u_range[0]) * column as f64 / resolution[0] as f64
+        };
+        let v_at =
+            |row: usize| v_range[0] + (v_range[1] - v_range[0]) * row as f64 / resolution[1] as f64;
+        let u = [u_at(columns[0]), u_at(columns[1])];
+        let v = [v_at(rows[0]), v_at(rows[1])];
+
+        if columns[1] - columns[0] == 1 && rows[1] - rows[0] == 1 {
+            return SurfaceNode {
+                bounds: cell_bounds(function, u, v),
+                u,
+                v,
+                children: Vec::new(),
+            };
+        }
+
+        let children = if columns[1] - columns[0] >= rows[1] - rows[0] {
+            let mid = (columns[0] + columns[1]) / 2;
+            vec![
+                SurfaceNode::new(
+                    function,
+                    u_range,
+                    v_range,
+                    [columns[0], mid],
+                    rows,
+                    resolution,
+                ),
+                SurfaceNode::new(
+                    function,
+                    u_range,
+                    v_range,
+                    [mid, columns[1]],
+                    rows,
+                    resolution,
+                ),
+            ]
+        } else {
+            let mid = (rows[0] + rows[1]) / 2;
+            vec![
+                SurfaceNode::new(
+                    function,
+                    u_range,
+                    v_range,
+                    columns,
+                    [rows[0], mid],
+                    resolution,
+                ),
+                SurfaceNode::new(
+                    function,
+                    u_range,
+                    v_range,
+                    columns,
+                    [mid, rows[1]],
+                    resolution,
+                ),
+            ]
+        };
+        SurfaceNode {
+            bounds: children[0].bounds.union(&children[1].bounds),
+            u,
+            v,
+            children,
+        }
+    }
+}
+
+/// Estima a caixa que envolve uma célula da superfície a partir de uma grade 3x3 de pontos
+/// dela. A folga é o dobro do quanto os pontos do meio fogem da média dos vizinhos
+/// (uma medida da curvatura), já que a superfície pode sair da caixa dos pontos amostrados.
+fn cell_bounds(function: &dyn Fn(f64, f64) -> DVec3, u: [f64; 2], v: [f64; 2]) -> Aabb {
+    let samples: [[DVec3; 3]; 3] = std::array::from_fn(|j| {
+        std::array::from_fn(|i| {
+            function(
+                u[0] + (u[1] - u[0]) * i as f64 / 2.0,
+                v[0] + (v[1] - v[0]) * j as f64 / 2.0,
+            )
+        })
+    });
+    let bounds = Aabb::from_points(samples.iter().flatten().copied());
+
+    let deviation = [
+        samples[0][1] - (samples[0][0] + samples[0][2]) / 2.0,
+        samples[2][1] - (samples[2][0] + samples[2][2]) / 2.0,
+        samples[1][0] - (samples[0][0] + samples[2][0]) / 2.0,
+        samples[1][2] - (samples[0][2] + samples[2][2]) / 2.0,
+        samples[1][1] - (samples[0][0] + samples[0][2] + samples[2][0] + samples[2][2]) / 4.0,
+    ]
+    .iter()
+    .map(|d| d.length())
+    .fold(0.0, f64::max);
+    // Folga mínima pra caixas achatadas (células planas)
+    bounds.padded(2.0 * deviation + 1e-9 * (bounds.max - bounds.min).length().max(1.0))
+}
@@ -1,6 +1,9 @@
 use glam::DVec3;
 
-use crate::{Intersection, Material, Ray, shapes::Shape};
+use crate::{
+    Aabb, Intersection, Lanes, Lanes3, Material, Ray, RayPacket,
+    shapes::{Shape, Span},
+};
 
 /// Plano definido por um ponto conhecido da superfície e sua normal
 pub struct Plane {
@@ -22,32 +25,112 @@ impl Plane {
         }
     }
 
+    /// O plano é ilimitado, então a caixa dele é o espaço todo
+    pub fn aabb(&self) -> Aabb {
+        Aabb::INFINITE
+    }
+
     /// Retorna os dados da interseção de um raio com o plano (None se não há interseção).
-    pub fn intersects(&self, ray: &Ray) -> Option<Intersection> {
-        // Resolve a equação em t (P - Pc) • n = 0
-        // --> t = -(p0-pc)•n/dr•n
-        let bottom = ray.dr.dot(self.normal);
-        // Se o termo de baixo é igual a 0, o raio é paralelo ao plano.
-        if bottom.abs() < 1e-8 {
-            return None;
-        }
-        let t = -(ray.p0 - self.pc).dot(self.normal) / bottom;
-        // Não retorna interseções atrás do p0 do raio
-        if t < 0.0 {
-            return None;
-        }
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let t = plane_intersects(ray, self.pc, self.normal)?;
         Some(Intersection {
-            t: t,
+            t,
             p: ray.at(t),
             normal: self.normal,
+            geometric_normal: self.normal,
             material: self.material,
+            barycentric: None,
             object: self,
         })
     }
+
+    /// `intersects` feito em todos os raios de um pacote ao mesmo tempo: retorna o t de
+    /// cada raio (infinito nos que não acertam o plano)
+    pub fn intersects_packet(&self, packet: &RayPacket) -> Lanes {
+        plane_intersects_packet(packet, self.pc, self.normal)
+    }
+
+    /// Retorna o intervalo do raio dentro do semiespaço atrás do plano
+    /// (o lado oposto ao da normal), que pode ser infinito
+    pub fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        let boundary = |t: f64| Intersection {
+            t,
+            p: ray.at(t),
+            normal: self.normal,
+            geometric_normal: self.normal,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        };
+        let Some(t) = plane_line_intersects(ray, self.pc, self.normal) else {
+            // Raio paralelo ao plano: ou está todo dentro do semiespaço, ou todo fora
+            if (ray.p0 - self.pc).dot(self.normal) > 0.0 {
+                return Vec::new();
+            }
+            return vec![Span {
+                entry: boundary(f64::NEG_INFINITY),
+                exit: boundary(f64::INFINITY),
+            }];
+        };
+        // Se o raio vai contra a normal, ele entra no semiespaço no plano e não sai mais
+        let span = if ray.dr.dot(self.normal) < 0.0 {
+            Span {
+                entry: boundary(t),
+                exit: boundary(f64::INFINITY),
+            }
+        } else {
+            Span {
+                entry: boundary(f64::NEG_INFINITY),
+                exit: boundary(t),
+            }
+        };
+        vec![span]
+    }
 }
 
 impl Shape for Plane {
-    fn intersects(&self, ray: &Ray) -> Option<Intersection> {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
         self.intersects(ray)
     }
+
+    fn intersects_packet(&self, packet: &RayPacket) -> Lanes {
+        self.intersects_packet(packet)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.spans(ray)
+    }
+}
+
+/// Retorna o t da interseção de um raio com o plano que passa por `pc` com normal `normal`
+/// (`None` se o raio é paralelo ao plano ou se a interseção está fora do intervalo do raio)
+pub(crate) fn plane_intersects(ray: &Ray, pc: DVec3, normal: DVec3) -> Option<f64> {
+    plane_line_intersects(ray, pc, normal).filter(|&t| ray.contains(t))
+}
+
+/// `plane_intersects` feito em todos os raios de um pacote ao mesmo tempo
+/// (infinito nos raios paralelos ao plano ou com a interseção fora do intervalo)
+pub(crate) fn plane_intersects_packet(packet: &RayPacket, pc: DVec3, normal: DVec3) -> Lanes {
+    let normal = Lanes3::splat(normal);
+    let bottom = packet.dr.dot(normal);
+    let t = -(packet.p0 - Lanes3::splat(pc)).dot(normal) / bottom;
+    packet.clip(t, bottom.0.map(|bottom| bottom != 0.0))
+}
+
+/// Retorna o t (de qualquer sinal) da interseção da reta do raio com o plano
+/// (`None` se o raio é paralelo ao plano)
+pub(crate) fn plane_line_intersects(ray: &Ray, pc: DVec3, normal: DVec3) -> Option<f64> {
+    // Resolve a equação em t (P - Pc) • n = 0
+    // --> t = -(p0-pc)•n/dr•n
+    let bottom = ray.dr.dot(normal);
+    // Se o termo de baixo é igual a 0, o raio é paralelo ao plano. Raios quase paralelos
+    // dão t enormes, que ficam de fora do intervalo do raio quando ele é limitado.
+    if bottom == 0.0 {
+        return None;
+    }
+    Some(-(ray.p0 - pc).dot(normal) / bottom)
 }
@@ -0,0 +1,250 @@
+use glam::DVec3;
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{Shape, disk::disk_intersects, grid::GridTraversal},
+};
+
+/// Nuvem de pontos renderizada como "splats": cada ponto é um disco de raio `radius`,
+/// perpendicular à normal do ponto (ou virado para o raio, se a nuvem não tem normais).
+/// Os discos não têm lado de dentro, então a normal sempre aponta para o lado de onde
+/// o raio veio. As cores dos pontos substituem as cores ambiente e difusa do material.
+pub struct PointCloud {
+    /// Material dos pontos (as cores dos pontos, se houver, substituem `k_amb` e `k_dif`)
+    pub material: Material,
+    points: Vec<DVec3>,
+    normals: Option<Vec<DVec3>>,
+    colors: Option<Vec<DVec3>>,
+    radius: f64,
+    /// Grade que divide o espaço em células, cada uma com os pontos que a tocam
+    grid: SplatGrid,
+}
+
+/// Grade regular de células com os índices dos pontos cujos discos tocam cada célula
+struct SplatGrid {
+    origin: DVec3,
+    cell_size: DVec3,
+    dimensions: [usize; 3],
+    /// Os pontos da célula `i` são `indices[cell_start[i]..cell_start[i + 1]]`
+    cell_start: Vec<usize>,
+    indices: Vec<usize>,
+}
+
+impl PointCloud {
+    /// Construtor da nuvem de pontos. Se presentes, `normals` e `colors` devem ter
+    /// um elemento por ponto. As cores vão de 0 a 1.
+    pub fn new(
+        points: Vec<DVec3>,
+        normals: Option<Vec<DVec3>>,
+        colors: Option<Vec<DVec3>>,
+        radius: f64,
+        material: Material,
+    ) -> PointCloud {
+        for attribute in [&normals, &colors].into_iter().flatten() {
+            assert_eq!(
+                attribute.len(),
+                points.len(),
+                "A nuvem precisa de exatamente uma normal/cor por ponto"
+            );
+        }
+        let normals = normals.map(|normals| {
+            normals
+                .into_iter()
+                .map(|n| n.try_normalize().unwrap_or(DVec3::Y))
+                .collect()
+        });
+        PointCloud {
+            grid: SplatGrid::new(&points, radius),
+            material,
+            points,
+            normals,
+            colors,
+            radius,
+        }
+    }
+
+    /// Posições dos pontos
+    pub fn points(&self) -> &[DVec3] {
+        &self.points
+    }
+
+    /// Normais dos pontos (None se os discos ficam virados para o raio)
+    pub fn normals(&self) -> Option<&[DVec3]> {
+        self.normals.as_deref()
+    }
+
+    /// Cores dos pontos
+    pub fn colors(&self) -> Option<&[DVec3]> {
+        self.colors.as_deref()
+    }
+
+    /// Raio dos discos
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    /// Caixa alinhada aos eixos que envolve os discos da nuvem
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.points.iter().copied()).padded(self.radius)
+    }
+
+    /// Retorna a interseção mais próxima de um raio com os discos (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let traversal = GridTraversal::new(
+            ray,
+            self.grid.origin,
+            self.grid.cell_size,
+            self.grid.dimensions,
+        )?;
+        for cell in traversal {
+            // Um disco pode estar em várias células: só vale a interseção que fica dentro
+            // da célula atual, já que as células mais próximas foram testadas antes
+            let closest = self
+                .grid
+                .cell(cell.index)
+                .iter()
+                .filter_map(|&i| Some((i, self.splat_intersects(ray, i)?)))
+                .filter(|&(_, (t, _))| t <= cell.t_exit)
+                .min_by(|(_, (t1, _)), (_, (t2, _))| t1.total_cmp(t2));
+
+            if let Some((i, (t, normal))) = closest {
+                let material = match &self.colors {
+                    Some(colors) => Material {
+                        k_amb: colors[i],
+                        k_dif: colors[i],
+                        ..self.material
+                    },
+                    None => self.material,
+                };
+                return Some(Intersection {
+                    t,
+                    p: ray.at(t),
+                    normal,
+                    geometric_normal: normal,
+                    material,
+                    barycentric: None,
+                    object: self,
+                });
+            }
+        }
+        None
+    }
+
+    /// Testa a interseção do raio com o disco do ponto `i`, retornando o t e a normal
+    fn splat_intersects(&self, ray: &Ray, i: usize) -> Option<(f64, DVec3)> {
+        let normal = match &self.normals {
+            Some(normals) => normals[i],
+            None => -ray.dr.normalize(),
+        };
+        let t = disk_intersects(ray, self.points[i], normal, self.radius)?;
+        let normal = if normal.dot(ray.dr) > 0.0 {
+            -normal
+        } else {
+            normal
+        };
+        Some((t, normal))
+    }
+}
+
+impl Shape for PointCloud {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
+
+impl SplatGrid {
+    /// Constrói a grade em volta dos discos, com em média alguns pontos por célula
+    fn new(points: &[DVec3], radius: f64) -> SplatGrid {
+        let min = points.iter().fold(DVec3::INFINITY, |min, &p| min.min(p)) - radius;
+        let max = points
+            .iter()
+            .fold(DVec3::NEG_INFINITY, |max, &p| max.max(p))
+            + radius;
+        if points.is_empty() {
+            return SplatGrid {
+                origin: DVec3::ZERO,
+                cell_size: DVec3::ONE,
+                dimensions: [0; 3],
+                cell_start: vec![0],
+                indices: Vec::new(),
+            };
+        }
+
+        // Células cúbicas, com aproximadamente tantas células quanto pontos
+        // (e sem deixar células menores que os discos)
+        let extent = (max - min).max(DVec3::splat(1e-9));
+        let side = (extent.x * extent.y * extent.z / points.len() as f64)
+            .cbrt()
+            .max(2.0 * radius);
+        let dimensions = extent
+            .to_array()
+            .map(|length| ((length / side).ceil() as usize).clamp(1, 256));
+        let cell_size = extent
+            / DVec3::new(
+                dimensions[0] as f64,
+                dimensions[1] as f64,
+                dimensions[2] as f64,
+            );
+
+        // Índices das células que a caixa de cada disco toca
+        let cell_range = |p: DVec3| {
+            let low = ((p - radius - min) / cell_size).floor();
+            let high = ((p + radius - min) / cell_size).floor();
+            let clamp = |v: f64, axis: usize| (v.max(0.0) as usize).min(dimensions[axis] - 1);
+            [0, 1, 2].map(|axis| clamp(low[axis], axis)..=clamp(high[axis], axis))
+        };
+        let cell_index = |x: usize, y: usize, z: usize| x + dimensions[0] * (y + dimensions[1] * z);
+
+        // Conta os pontos de cada célula, e depois guarda os índices deles
+        // em sequência, célula por célula
+        let cell_count = dimensions.iter().product::<usize>();
+        let mut counts = vec![0; cell_count];
+        for &p in points {
+            let [xs, ys, zs] = cell_range(p);
+            for z in zs {
+                for y in ys.clone() {
+                    for x in xs.clone() {
+                        counts[cell_index(x, y, z)] += 1;
+                    }
+                }
+            }
+        }
+        let mut cell_start = Vec::with_capacity(cell_count + 1);
+        cell_start.push(0);
+        for count in &counts {
+            cell_start.push(cell_start.last().unwrap() + count);
+        }
+        let mut next = cell_start.clone();
+        let mut indices = vec![0; *cell_start.last().unwrap()];
+        for (i, &p) in points.iter().enumerate() {
+            let [xs, ys, zs] = cell_range(p);
+            for z in zs {
+                for y in ys.clone() {
+                    for x in xs.clone() {
+                        let cell = cell_index(x, y, z);
+                        indices[next[cell]] = i;
+                        next[cell] += 1;
+                    }
+                }
+            }
+        }
+
+        SplatGrid {
+            origin: min,
+            cell_size,
+            dimensions,
+            cell_start,
+            indices,
+        }
+    }
+
+    /// Índices dos pontos que tocam a célula
+    fn cell(&self, [x, y, z]: [usize; 3]) -> &[usize] {
+        let i = x + self.dimensions[0] * (y + self.dimensions[1] * z);
+        &self.indices[self.cell_start[i]..self.cell_start[i + 1]]
+    }
+}
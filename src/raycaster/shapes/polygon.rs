@@ -0,0 +1,112 @@
+use glam::{DVec2, DVec3};
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{Shape, plane::plane_intersects},
+};
+
+/// Polígono plano (convexo ou côncavo) definido pela lista de seus vértices
+pub struct Polygon {
+    /// Vértices do polígono, em ordem (anti-horária vista de frente)
+    pub vertices: Vec<DVec3>,
+    /// Vetor normal ao plano do polígono
+    pub normal: DVec3,
+    /// Material do polígono
+    pub material: Material,
+}
+
+impl Polygon {
+    /// Construtor do polígono. Os vértices devem estar todos no mesmo plano.
+    pub fn new(vertices: Vec<DVec3>, material: Material) -> Polygon {
+        assert!(
+            vertices.len() >= 3,
+            "Um polígono precisa de pelo menos 3 vértices"
+        );
+        // Método de Newell: funciona para polígonos côncavos e vértices colineares
+        let normal = vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .map(|(a, b)| {
+                DVec3::new(
+                    (a.y - b.y) * (a.z + b.z),
+                    (a.z - b.z) * (a.x + b.x),
+                    (a.x - b.x) * (a.y + b.y),
+                )
+            })
+            .sum::<DVec3>()
+            .normalize();
+        Polygon {
+            vertices,
+            normal,
+            material,
+        }
+    }
+
+    /// Caixa alinhada aos eixos que envolve o polígono
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.vertices.iter().copied())
+    }
+
+    /// Retorna a interseção de um raio com o polígono (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let t = plane_intersects(ray, self.vertices[0], self.normal)?;
+        let p = ray.at(t);
+        if !self.contains(p) {
+            return None;
+        }
+        Some(Intersection {
+            t,
+            p,
+            normal: self.normal,
+            geometric_normal: self.normal,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        })
+    }
+
+    /// Testa se um ponto do plano do polígono está dentro dele
+    fn contains(&self, p: DVec3) -> bool {
+        // Projeta tudo no plano coordenado em que o polígono tem a maior área,
+        // descartando o eixo de maior componente da normal
+        let n = self.normal.abs();
+        let project = |v: DVec3| {
+            if n.x >= n.y && n.x >= n.z {
+                DVec2::new(v.y, v.z)
+            } else if n.y >= n.z {
+                DVec2::new(v.z, v.x)
+            } else {
+                DVec2::new(v.x, v.y)
+            }
+        };
+        let p = project(p);
+
+        // Teste par-ímpar: conta quantas arestas uma semirreta saindo de p
+        // na direção +x cruza
+        let mut inside = false;
+        for (a, b) in self
+            .vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+        {
+            let (a, b) = (project(*a), project(*b));
+            if (a.y > p.y) != (b.y > p.y) {
+                let x_cross = a.x + (p.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if p.x < x_cross {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+}
+
+impl Shape for Polygon {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
@@ -0,0 +1,81 @@
+use glam::DVec3;
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{Shape, plane::plane_intersects},
+};
+
+/// Paralelogramo definido por uma quina e os dois lados que saem dela
+/// (um retângulo se os lados forem perpendiculares)
+pub struct Quad {
+    /// Quina do paralelogramo
+    pub corner: DVec3,
+    /// Primeiro lado, saindo da quina
+    pub u: DVec3,
+    /// Segundo lado, saindo da quina
+    pub v: DVec3,
+    /// Vetor normal ao paralelogramo (u x v normalizado)
+    pub normal: DVec3,
+    /// Material do paralelogramo
+    pub material: Material,
+}
+
+impl Quad {
+    /// Construtor do paralelogramo. A normal aponta na direção de u x v.
+    pub fn new(corner: DVec3, u: DVec3, v: DVec3, material: Material) -> Quad {
+        Quad {
+            corner,
+            u,
+            v,
+            normal: u.cross(v).normalize(),
+            material,
+        }
+    }
+
+    /// Caixa alinhada aos eixos que envolve o quadrilátero
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points([
+            self.corner,
+            self.corner + self.u,
+            self.corner + self.v,
+            self.corner + self.u + self.v,
+        ])
+    }
+
+    /// Retorna a interseção de um raio com o paralelogramo (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let t = plane_intersects(ray, self.corner, self.normal)?;
+        let p = ray.at(t);
+
+        // Escreve o ponto como corner + alpha*u + beta*v; ele está dentro do
+        // paralelogramo se alpha e beta estão entre 0 e 1
+        let n = self.u.cross(self.v);
+        let w = n / n.length_squared();
+        let cp = p - self.corner;
+        let alpha = w.dot(cp.cross(self.v));
+        let beta = w.dot(self.u.cross(cp));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        Some(Intersection {
+            t,
+            p,
+            normal: self.normal,
+            geometric_normal: self.normal,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        })
+    }
+}
+
+impl Shape for Quad {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
@@ -0,0 +1,323 @@
+use glam::{DMat2, DMat3, DMat4, DVec2, DVec3, DVec4};
+
+use crate::{Aabb, Intersection, Material, Ray, shapes::Shape, solvers::solve_quadratic};
+
+/// Superfície quádrica geral, definida pela equação Pᵀ * Q * P = 0,
+/// onde P = (x, y, z, 1) e Q é uma matriz 4x4 simétrica de coeficientes
+pub struct Quadric {
+    /// Matriz simétrica de coeficientes Q
+    pub coefficients: DMat4,
+    /// Corte opcional da superfície por altura ao longo de um eixo
+    pub clip: Option<QuadricClip>,
+    /// Material da quádrica
+    pub material: Material,
+}
+
+/// Corte de uma quádrica: só os pontos P com `min_height <= (P - base)•axis <= max_height`
+/// fazem parte da superfície
+#[derive(Clone, Copy)]
+pub struct QuadricClip {
+    /// Ponto de altura 0
+    pub base: DVec3,
+    /// Direção em que a altura é medida
+    pub axis: DVec3,
+    /// Altura mínima
+    pub min_height: f64,
+    /// Altura máxima
+    pub max_height: f64,
+}
+
+impl Quadric {
+    /// Construtor da quádrica a partir da matriz de coeficientes
+    /// (que é simetrizada, caso não seja)
+    pub fn new(coefficients: DMat4, clip: Option<QuadricClip>, material: Material) -> Quadric {
+        Quadric {
+            coefficients: (coefficients + coefficients.transpose()) * 0.5,
+            clip: clip.map(|clip| QuadricClip {
+                axis: clip.axis.normalize(),
+                ..clip
+            }),
+            material,
+        }
+    }
+
+    /// Elipsoide com centro e semieixos nas direções x, y e z:
+    /// x²/a² + y²/b² + z²/c² = 1
+    pub fn ellipsoid(center: DVec3, radii: DVec3, material: Material) -> Quadric {
+        let local = DMat4::from_diagonal(DVec4::new(
+            1.0 / (radii.x * radii.x),
+            1.0 / (radii.y * radii.y),
+            1.0 / (radii.z * radii.z),
+            -1.0,
+        ));
+        Quadric::new(translated(local, center), None, material)
+    }
+
+    /// Paraboloide elíptico com o vértice dado, abrindo na direção +y:
+    /// x²/a² + z²/c² = y/h, cortado entre y = 0 e y = h.
+    /// Na altura máxima, a seção tem semieixos `radius_x` e `radius_z`.
+    pub fn elliptic_paraboloid(
+        vertex: DVec3,
+        radius_x: f64,
+        radius_z: f64,
+        height: f64,
+        material: Material,
+    ) -> Quadric {
+        let mut local = DMat4::from_diagonal(DVec4::new(
+            1.0 / (radius_x * radius_x),
+            0.0,
+            1.0 / (radius_z * radius_z),
+            0.0,
+        ));
+        // Termo linear -y/h, dividido entre os dois elementos simétricos
+        local.y_axis.w = -0.5 / height;
+        local.w_axis.y = -0.5 / height;
+        let clip = QuadricClip {
+            base: vertex,
+            axis: DVec3::Y,
+            min_height: 0.0,
+            max_height: height,
+        };
+        Quadric::new(translated(local, vertex), Some(clip), material)
+    }
+
+    /// Hiperboloide de uma folha centrado em `center`, com eixo na direção y:
+    /// x²/a² - y²/b² + z²/c² = 1, cortado em |y| <= height/2
+    pub fn hyperboloid_one_sheet(
+        center: DVec3,
+        radii: DVec3,
+        height: f64,
+        material: Material,
+    ) -> Quadric {
+        let local = DMat4::from_diagonal(DVec4::new(
+            1.0 / (radii.x * radii.x),
+            -1.0 / (radii.y * radii.y),
+            1.0 / (radii.z * radii.z),
+            -1.0,
+        ));
+        Quadric::new(
+            translated(local, center),
+            Some(centered_clip(center, height)),
+            material,
+        )
+    }
+
+    /// Hiperboloide de duas folhas centrado em `center`, com eixo na direção y:
+    /// -x²/a² + y²/b² - z²/c² = 1, cortado em |y| <= height/2
+    pub fn hyperboloid_two_sheets(
+        center: DVec3,
+        radii: DVec3,
+        height: f64,
+        material: Material,
+    ) -> Quadric {
+        let local = DMat4::from_diagonal(DVec4::new(
+            -1.0 / (radii.x * radii.x),
+            1.0 / (radii.y * radii.y),
+            -1.0 / (radii.z * radii.z),
+            -1.0,
+        ));
+        Quadric::new(
+            translated(local, center),
+            Some(centered_clip(center, height)),
+            material,
+        )
+    }
+
+    /// Caixa alinhada aos eixos que envolve a quádrica. Sem corte, só os elipsoides são
+    /// limitados (as outras quádricas usam a caixa do espaço todo); com corte, a parte
+    /// cortada também é limitada quando as seções paralelas às bordas do corte são elipses.
+    pub fn aabb(&self) -> Aabb {
+        let unclipped = self.unclipped_aabb();
+        match &self.clip {
+            Some(clip) => unclipped.intersection(&self.clipped_aabb(clip)),
+            None => unclipped,
+        }
+    }
+
+    /// Caixa da quádrica inteira (sem o corte): a do elipsoide, ou a do espaço todo
+    fn unclipped_aabb(&self) -> Aabb {
+        // A parte quadrática (o bloco 3x3) só é definida, positiva ou negativa, nos elipsoides
+        let a = DMat3::from_mat4(self.coefficients);
+        let minors = [
+            a.x_axis.x,
+            a.x_axis.x * a.y_axis.y - a.x_axis.y * a.y_axis.x,
+            a.determinant(),
+        ];
+        let positive = minors.iter().all(|&minor| minor > 0.0);
+        let negative = minors[0] < 0.0 && minors[1] > 0.0 && minors[2] < 0.0;
+        if !(positive || negative) || self.coefficients.determinant() == 0.0 {
+            return Aabb::INFINITE;
+        }
+
+        // O plano π = (e, -s), de equação x_e = s, tangencia a quádrica quando πᵀ * Q⁻¹ * π = 0,
+        // ou seja, Q⁻¹[e][e] - 2s * Q⁻¹[e][w] + s² * Q⁻¹[w][w] = 0. As duas raízes são
+        // os limites da caixa no eixo e.
+        let dual = self.coefficients.inverse();
+        let mut min = DVec3::ZERO;
+        let mut max = DVec3::ZERO;
+        for axis in 0..3 {
+            let column = dual.col(axis);
+            let Some([s1, s2]) = solve_quadratic(dual.w_axis.w, -2.0 * column.w, column[axis])
+            else {
+                // Sem planos tangentes, o elipsoide é imaginário (não tem nenhum ponto)
+                return Aabb::EMPTY;
+            };
+            min[axis] = s1.min(s2);
+            max[axis] = s1.max(s2);
+        }
+        Aabb::new(min, max)
+    }
+
+    /// Caixa da parte da quádrica que está dentro do corte. Em cada eixo, os pontos mais
+    /// extremos dessa parte estão nas cônicas das bordas do corte, ou são pontos de
+    /// tangência de planos perpendiculares ao eixo que ficam entre as bordas.
+    fn clipped_aabb(&self, clip: &QuadricClip) -> Aabb {
+        if !clip.min_height.is_finite() || !clip.max_height.is_finite() {
+            return Aabb::INFINITE;
+        }
+        // Q = [A b; bᵀ d]
+        let a = DMat3::from_mat4(self.coefficients);
+        let b = self.coefficients.w_axis.truncate();
+        let d = self.coefficients.w_axis.w;
+
+        // As seções do corte são os pontos c0 + x*u + y*v (u e v unitários e perpendiculares
+        // ao eixo, c0 na altura da seção), com xᵀBx + 2xᵀβ + γ = 0. A parte quadrática B é a
+        // mesma em todas as alturas: se ela não é definida, as seções são hipérboles ou
+        // parábolas, e a parte cortada é ilimitada.
+        let (u, v) = clip.axis.any_orthonormal_pair();
+        let section = DMat2::from_cols(
+            DVec2::new(u.dot(a * u), v.dot(a * u)),
+            DVec2::new(u.dot(a * v), v.dot(a * v)),
+        );
+        if section.determinant() <= 0.0 {
+            return Aabb::INFINITE;
+        }
+        // Troca o sinal da equação se preciso, pra que B seja positiva
+        let sign = section.x_axis.x.signum();
+        let inverse = (section * sign).inverse();
+
+        let mut points = Vec::new();
+        for height in [clip.min_height, clip.max_height] {
+            let c0 = clip.base + clip.axis * height;
+            let beta = DVec2::new(u.dot(a * c0 + b), v.dot(a * c0 + b)) * sign;
+            let gamma = (c0.dot(a * c0) + 2.0 * b.dot(c0) + d) * sign;
+            // Com x = centro + y: yᵀBy = k, uma elipse (k > 0), um ponto (k = 0) ou nada (k < 0)
+            let center = -(inverse * beta);
+            let k = beta.dot(inverse * beta) - gamma;
+            if k < 0.0 {
+                continue;
+            }
+            for axis in 0..3 {
+                // O ponto da elipse mais longe do centro no eixo está na direção B⁻¹g
+                let g = DVec2::new(u[axis], v[axis]);
+                let spread = g.dot(inverse * g);
+                if spread <= 0.0 {
+                    continue;
+                }
+                let offset = inverse * g * (k / spread).sqrt();
+                for x in [center + offset, center - offset] {
+                    points.push(c0 + u * x.x + v * x.y);
+                }
+            }
+        }
+
+        // Pontos de tangência dos planos x_e = s (ver `unclipped_aabb`): o plano π tangencia
+        // a quádrica no ponto Q⁻¹ * π. Quádricas degeneradas (cones e cilindros) não têm
+        // esses pontos fora das bordas, já que os extremos delas ficam ao longo de retas.
+        if self.coefficients.determinant() != 0.0 {
+            // Tolerância na altura, pra não perder pontos em cima das bordas (como o vértice
+            // do paraboloide) por erro de arredondamento
+            let tolerance = 1e-9 * clip.min_height.abs().max(clip.max_height.abs()).max(1.0);
+            let heights = (clip.min_height - tolerance)..=(clip.max_height + tolerance);
+            let dual = self.coefficients.inverse();
+            for axis in 0..3 {
+                let column = dual.col(axis);
+                let roots = solve_quadratic(dual.w_axis.w, -2.0 * column.w, column[axis]);
+                for s in roots.into_iter().flatten() {
+                    let mut plane = DVec4::ZERO;
+                    plane[axis] = 1.0;
+                    plane.w = -s;
+                    let tangent = dual * plane;
+                    let p = tangent.truncate() / tangent.w;
+                    if p.is_finite() && heights.contains(&(p - clip.base).dot(clip.axis)) {
+                        points.push(p);
+                    }
+                }
+            }
+        }
+
+        let bounds = Aabb::from_points(points);
+        // Folga pros erros de arredondamento das contas acima
+        bounds.padded(1e-9 * (bounds.max - bounds.min).length().max(1.0))
+    }
+
+    /// Retorna a interseção mais próxima de um raio com a quádrica (None se não há interseção).
+    /// Como as quádricas cortadas são superfícies abertas, a normal sempre aponta
+    /// para o lado de onde o raio veio.
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        // Substituindo P = O + t*D em PᵀQP = 0 (O = (p0, 1), D = (dr, 0)):
+        // t²*DᵀQD + 2t*DᵀQO + OᵀQO = 0
+        let o = ray.p0.extend(1.0);
+        let d = ray.dr.extend(0.0);
+        let qo = self.coefficients * o;
+        let qd = self.coefficients * d;
+        let [t1, t2] = solve_quadratic(d.dot(qd), 2.0 * d.dot(qo), o.dot(qo))?;
+
+        // Testa as 2 raízes, já que a mais próxima pode ter sido cortada
+        [t1, t2]
+            .into_iter()
+            .filter(|&t| ray.contains(t))
+            .map(|t| (t, ray.at(t)))
+            .find(|(_, p)| self.is_inside_clip(*p))
+            .map(|(t, p)| {
+                // Normal = gradiente de PᵀQP = 2QP
+                let mut normal = (self.coefficients * p.extend(1.0)).truncate().normalize();
+                if normal.dot(ray.dr) > 0.0 {
+                    normal = -normal;
+                }
+                Intersection {
+                    t,
+                    p,
+                    normal,
+                    geometric_normal: normal,
+                    material: self.material,
+                    barycentric: None,
+                    object: self,
+                }
+            })
+    }
+
+    /// Testa se um ponto da superfície está dentro da região de corte
+    fn is_inside_clip(&self, p: DVec3) -> bool {
+        self.clip.is_none_or(|clip| {
+            let height = (p - clip.base).dot(clip.axis);
+            (clip.min_height..=clip.max_height).contains(&height)
+        })
+    }
+}
+
+impl Shape for Quadric {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
+
+/// Move uma quádrica definida em torno da origem para `offset`: Q' = T⁻ᵀ * Q * T⁻¹
+fn translated(local: DMat4, offset: DVec3) -> DMat4 {
+    let inverse = DMat4::from_translation(-offset);
+    inverse.transpose() * local * inverse
+}
+
+/// Corte simétrico de altura total `height` ao longo de y, centrado em `center`
+fn centered_clip(center: DVec3, height: f64) -> QuadricClip {
+    QuadricClip {
+        base: center,
+        axis: DVec3::Y,
+        min_height: -height / 2.0,
+        max_height: height / 2.0,
+    }
+}
@@ -0,0 +1,184 @@
+use glam::DVec3;
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{
+        Shape, Span, Torus,
+        disk::{disk_aabb, disk_line_intersects},
+    },
+    solvers::solve_quadratic,
+};
+
+/// Cilindro fechado com as quinas da base e do topo arredondadas por um filete
+/// (um quarto de toro) de raio `fillet_radius`
+pub struct RoundedCilinder {
+    /// Centro da base do cilindro
+    pub cb: DVec3,
+    /// Direção do eixo do cilindro
+    pub dc: DVec3,
+    /// Altura do cilindro
+    pub height: f64,
+    /// Raio do cilindro
+    pub radius: f64,
+    /// Raio do arredondamento das quinas
+    pub fillet_radius: f64,
+    /// Material do cilindro
+    pub material: Material,
+}
+
+impl RoundedCilinder {
+    /// Construtor do cilindro arredondado. O raio do filete é limitado ao raio
+    /// do cilindro e à metade da altura.
+    pub fn new(
+        cb: DVec3,
+        dc: DVec3,
+        height: f64,
+        radius: f64,
+        fillet_radius: f64,
+        material: Material,
+    ) -> RoundedCilinder {
+        RoundedCilinder {
+            cb,
+            dc: dc.normalize(),
+            height,
+            radius,
+            fillet_radius: fillet_radius.clamp(0.0, radius.min(height / 2.0)),
+            material,
+        }
+    }
+
+    /// Caixa alinhada aos eixos que envolve o cilindro (a das suas duas bases)
+    pub fn aabb(&self) -> Aabb {
+        let ct = self.cb + self.dc * self.height;
+        disk_aabb(self.cb, self.dc, self.radius).union(&disk_aabb(ct, self.dc, self.radius))
+    }
+
+    /// Retorna a interseção mais próxima de um raio com o cilindro (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.line_hits(ray)
+            .into_iter()
+            .filter(|&t| ray.contains(t))
+            .min_by(|t1, t2| t1.total_cmp(t2))
+            .map(|t| self.intersection_at(ray, t))
+    }
+
+    /// Retorna o intervalo do raio que está dentro do cilindro (vazio se não há interseção)
+    pub fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        // O cilindro é convexo: a reta entra na primeira interseção e sai na última
+        let hits = self.line_hits(ray);
+        let entry = hits.iter().copied().min_by(|t1, t2| t1.total_cmp(t2));
+        let exit = hits.iter().copied().max_by(|t1, t2| t1.total_cmp(t2));
+        match (entry, exit) {
+            (Some(entry), Some(exit)) if entry < exit => vec![Span {
+                entry: self.intersection_at(ray, entry),
+                exit: self.intersection_at(ray, exit),
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Valores de t (de qualquer sinal) em que a reta do raio cruza a borda do cilindro
+    fn line_hits(&self, ray: &Ray) -> Vec<f64> {
+        let r = self.fillet_radius;
+        // Raio dos discos da base e do topo, que ficam dentro dos filetes
+        let inner_radius = self.radius - r;
+        let height_at = |t: f64| (ray.at(t) - self.cb).dot(self.dc);
+        let radial_at = |t: f64| {
+            (ray.at(t) - self.cb)
+                .reject_from_normalized(self.dc)
+                .length()
+        };
+        let mut hits = Vec::new();
+
+        // Superfície cilíndrica, entre os dois filetes
+        let mdr = ray.dr.reject_from_normalized(self.dc);
+        let mw = (ray.p0 - self.cb).reject_from_normalized(self.dc);
+        if let Some(roots) = solve_quadratic(
+            mdr.length_squared(),
+            2.0 * mdr.dot(mw),
+            mw.length_squared() - self.radius * self.radius,
+        ) {
+            hits.extend(
+                roots
+                    .into_iter()
+                    .filter(|&t| (r..=self.height - r).contains(&height_at(t))),
+            );
+        }
+
+        // Discos planos da base e do topo
+        let ct = self.cb + self.dc * self.height;
+        for (center, normal) in [(self.cb, -self.dc), (ct, self.dc)] {
+            hits.extend(disk_line_intersects(ray, center, normal, inner_radius));
+        }
+
+        // Filetes: a parte de fora e de baixo do toro da base, e de fora e de cima do
+        // toro do topo. Sem disco no meio, o toro vira uma esfera.
+        for (center_height, side) in [(r, -1.0), (self.height - r, 1.0)] {
+            let center = self.cb + self.dc * center_height;
+            let roots = if inner_radius > 1e-9 {
+                Torus::new(center, self.dc, inner_radius, r, self.material).line_roots(ray)
+            } else {
+                let oc = ray.p0 - center;
+                solve_quadratic(
+                    ray.dr.length_squared(),
+                    2.0 * ray.dr.dot(oc),
+                    oc.length_squared() - r * r,
+                )
+                .map(Vec::from)
+                .unwrap_or_default()
+            };
+            hits.extend(roots.into_iter().filter(|&t| {
+                (height_at(t) - center_height) * side > 0.0 && radial_at(t) >= inner_radius
+            }));
+        }
+        hits
+    }
+
+    /// Constrói a interseção no ponto t do raio. O cilindro arredondado é um cilindro
+    /// menor "engordado" pelo raio do filete, então a normal aponta do ponto mais próximo
+    /// desse cilindro menor até o ponto, o que a deixa contínua nas emendas.
+    fn intersection_at(&self, ray: &Ray, t: f64) -> Intersection<'_> {
+        let r = self.fillet_radius;
+        let p = ray.at(t);
+        let cp = p - self.cb;
+        let height = cp.dot(self.dc).clamp(r, self.height - r);
+        let radial = cp
+            .reject_from_normalized(self.dc)
+            .clamp_length_max(self.radius - r);
+        let closest = self.cb + self.dc * height + radial;
+        // Sem filete, o ponto e o mais próximo coincidem: usa a normal da face plana
+        // ou da superfície cilíndrica em que o ponto está
+        let normal = (p - closest).try_normalize().unwrap_or_else(|| {
+            if cp.dot(self.dc) <= 1e-9 {
+                -self.dc
+            } else if cp.dot(self.dc) >= self.height - 1e-9 {
+                self.dc
+            } else {
+                radial.normalize()
+            }
+        });
+        Intersection {
+            t,
+            p,
+            normal,
+            geometric_normal: normal,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        }
+    }
+}
+
+impl Shape for RoundedCilinder {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.spans(ray)
+    }
+}
@@ -0,0 +1,105 @@
+use glam::DVec3;
+
+use crate::{Aabb, Intersection, Material, Ray, shapes::Shape};
+
+/// Forma definida por uma função de distância com sinal (SDF): negativa dentro do sólido,
+/// positiva fora e zero na superfície. É renderizada por "sphere tracing", então a função
+/// não pode superestimar a distância até a superfície. O módulo `sdf` tem primitivas
+/// e operações prontas para montar essas funções.
+pub struct Sdf {
+    /// Função de distância até a superfície
+    pub distance: Box<dyn Fn(DVec3) -> f64 + Send + Sync>,
+    /// Material da forma
+    pub material: Material,
+    /// Número máximo de passos da marcha ao longo do raio
+    pub max_steps: usize,
+    /// Distância máxima percorrida ao longo do raio antes de desistir
+    pub max_distance: f64,
+    /// Distância até a superfície considerada como colisão
+    pub epsilon: f64,
+}
+
+impl Sdf {
+    /// Construtor da forma, com 256 passos, distância máxima 1000 e epsilon 1e-4
+    pub fn new(distance: impl Fn(DVec3) -> f64 + Send + Sync + 'static, material: Material) -> Sdf {
+        Sdf {
+            distance: Box::new(distance),
+            material,
+            max_steps: 256,
+            max_distance: 1000.0,
+            epsilon: 1e-4,
+        }
+    }
+
+    /// A distância pode descrever qualquer coisa, então a caixa da superfície é o espaço todo
+    pub fn aabb(&self) -> Aabb {
+        Aabb::INFINITE
+    }
+
+    /// Retorna a interseção mais próxima de um raio com a superfície (None se não há interseção
+    /// dentro do número máximo de passos, da distância máxima e do intervalo do raio)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        // A direção do raio pode não ser unitária: as distâncias são divididas pelo
+        // tamanho dela para virarem passos em t
+        let dr_length = ray.dr.length();
+        let t_max = (self.max_distance / dr_length).min(ray.t_max);
+        let mut t = ray.t_min;
+        // Pontos a menos de `epsilon` da superfície já contam como colisão, e essa folga é
+        // bem maior que o afastamento da origem dos raios secundários (`Ray::from_surface`).
+        // Então um raio que começa nessa faixa só pode colidir depois de sair dela, senão
+        // a marcha pararia no próprio ponto de partida.
+        let mut left_surface = false;
+
+        for _ in 0..self.max_steps {
+            let distance = (self.distance)(ray.at(t)).abs();
+            if distance < self.epsilon {
+                if left_surface {
+                    let p = ray.at(t);
+                    let normal = self.normal_at(p);
+                    return Some(Intersection {
+                        t,
+                        p,
+                        normal,
+                        geometric_normal: normal,
+                        material: self.material,
+                        barycentric: None,
+                        object: self,
+                    });
+                }
+            } else {
+                left_surface = true;
+            }
+
+            // Nenhuma superfície está a menos de `distance` do ponto, então dá pra
+            // andar isso tudo sem atravessar nada
+            t += distance.max(self.epsilon) / dr_length;
+            if t >= t_max {
+                break;
+            }
+        }
+        None
+    }
+
+    /// Normal num ponto da superfície: o gradiente da função de distância,
+    /// calculado por diferenças centrais
+    fn normal_at(&self, p: DVec3) -> DVec3 {
+        let h = self.epsilon;
+        let d = &self.distance;
+        DVec3::new(
+            d(p + DVec3::X * h) - d(p - DVec3::X * h),
+            d(p + DVec3::Y * h) - d(p - DVec3::Y * h),
+            d(p + DVec3::Z * h) - d(p - DVec3::Z * h),
+        )
+        .normalize()
+    }
+}
+
+impl Shape for Sdf {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
-    shapes::Shape,
-    raycaster::{Intersection, Material, Ray},
+    shapes::{Shape, Span},
+    raycaster::{Aabb, Intersection, Lanes, Lanes3, Material, Ray, RayPacket},
+    solvers::{solve_quadratic, solve_quadratic_packet},
 };
 use glam::DVec3;
 
@@ -24,8 +25,48 @@ impl Sphere {
         }
     }
 
+    /// Caixa alinhada aos eixos que envolve a esfera
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_center(self.pos, DVec3::splat(self.radius))
+    }
+
     /// Retorna a interseção mais próxima de um raio com uma esfera (None se não há interseção)
-    pub fn intersects(&self, ray: &Ray) -> Option<Intersection> {
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        // Pega o t mais próximo dentro do intervalo do raio e constrói um struct
+        // da interseção nesse t (None se não há t no intervalo)
+        let [t1, t2] = self.roots(ray)?;
+        [t1, t2]
+            .into_iter()
+            .filter(|&t| ray.contains(t))
+            .min_by(|t1, t2| t1.total_cmp(t2))
+            .map(|t| self.intersection_at(ray, t))
+    }
+
+    /// `intersects` feito em todos os raios de um pacote ao mesmo tempo: retorna o t de
+    /// cada raio (infinito nos que não acertam a esfera)
+    pub fn intersects_packet(&self, packet: &RayPacket) -> Lanes {
+        // Mesma equação de `roots`, em cada raio
+        let oc = packet.p0 - Lanes3::splat(self.pos);
+        let a = packet.dr.length_squared();
+        let b = packet.dr.dot(oc) * 2.0;
+        let c = oc.length_squared() - self.radius * self.radius;
+        let ([t1, t2], real) = solve_quadratic_packet(a, b, c);
+        packet.clip(t1, real).min(packet.clip(t2, real))
+    }
+
+    /// Retorna o intervalo do raio que está dentro da esfera (vazio se não há interseção)
+    pub fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        match self.roots(ray) {
+            Some([t1, t2]) => vec![Span {
+                entry: self.intersection_at(ray, t1),
+                exit: self.intersection_at(ray, t2),
+            }],
+            None => Vec::new(),
+        }
+    }
+
+    /// Valores de t (de qualquer sinal) em que a reta do raio cruza a superfície da esfera
+    fn roots(&self, ray: &Ray) -> Option<[f64; 2]> {
         // resolvemos a equação do segundo grau |R(t) - C| = r
         // t²*dr•dr + 2t*dr•v + v•v - r² = 0
         // v = p0 - ce (centro da esfera)
@@ -36,36 +77,40 @@ impl Sphere {
         let a = ray.dr.length_squared();
         let b = 2.0 * ray.dr.dot(oc);
         let c = oc.length_squared() - self.radius * self.radius;
-        let delta = b * b - 4.0 * a * c;
 
-        if delta < 0.0 {
-            return None;
-        }
+        solve_quadratic(a, b, c)
+    }
 
-        // Pega o t > 0 mais próximo e constrói um struct da interseção nesse t
-        // (None se não há t > 0)
-        let t1 = (-b + delta.sqrt()) / (2.0 * a);
-        let t2 = (-b - delta.sqrt()) / (2.0 * a);
-        [t1, t2]
-            .into_iter()
-            .filter(|&t| t > 0.0)
-            .min_by(|t1, t2| t1.total_cmp(t2))
-            .map(|t| {
-                let p = ray.at(t);
-                let n = (p - self.pos).normalize();
-                Intersection {
-                    t: t,
-                    p: p,
-                    normal: n,
-                    material: self.material,
-                    object: self,
-                }
-            })
+    /// Constrói o struct da interseção no ponto t do raio
+    fn intersection_at(&self, ray: &Ray, t: f64) -> Intersection<'_> {
+        let p = ray.at(t);
+        let n = (p - self.pos).normalize();
+        Intersection {
+            t,
+            p,
+            normal: n,
+            geometric_normal: n,
+            material: self.material,
+            barycentric: None,
+            object: self,
+        }
     }
 }
 
 impl Shape for Sphere {
-    fn intersects(&self, ray: &Ray) -> Option<Intersection> {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
         self.intersects(ray)
     }
+
+    fn intersects_packet(&self, packet: &RayPacket) -> Lanes {
+        self.intersects_packet(packet)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+
+    fn spans(&self, ray: &Ray) -> Vec<Span<'_>> {
+        self.spans(ray)
+    }
 }
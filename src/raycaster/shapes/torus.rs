@@ -0,0 +1,130 @@
+use glam::DVec3;
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{Shape, disk::disk_aabb},
+    solvers::{solve_quadratic, solve_quartic},
+};
+
+/// Toro definido pelo seu centro, pela direção do seu eixo de revolução,
+/// pelo raio maior (do centro até o meio do tubo) e pelo raio menor (do tubo)
+pub struct Torus {
+    /// Centro do toro
+    pub center: DVec3,
+    /// Direção do eixo de revolução do toro
+    pub axis: DVec3,
+    /// Raio maior: distância do centro do toro até o centro do tubo
+    pub major_radius: f64,
+    /// Raio menor: raio do tubo
+    pub minor_radius: f64,
+    /// Material do toro
+    pub material: Material,
+}
+
+impl Torus {
+    /// Construtor do toro
+    pub fn new(
+        center: DVec3,
+        axis: DVec3,
+        major_radius: f64,
+        minor_radius: f64,
+        material: Material,
+    ) -> Torus {
+        Torus {
+            center,
+            axis: axis.normalize(),
+            major_radius,
+            minor_radius,
+            material,
+        }
+    }
+
+    /// Caixa alinhada aos eixos que envolve o toro: a do círculo central, aumentada do raio do tubo
+    pub fn aabb(&self) -> Aabb {
+        disk_aabb(self.center, self.axis, self.major_radius).padded(self.minor_radius)
+    }
+
+    /// Retorna a interseção mais próxima de um raio com o toro (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.line_roots(ray)
+            .into_iter()
+            // As raízes já vêm em ordem crescente: pega a primeira dentro do intervalo do raio
+            .find(|&t| ray.contains(t))
+            .map(|t| {
+                let p = ray.at(t);
+                let normal = self.normal_at(p);
+                Intersection {
+                    t,
+                    p,
+                    normal,
+                    geometric_normal: normal,
+                    material: self.material,
+                    barycentric: None,
+                    object: self,
+                }
+            })
+    }
+
+    /// Valores de t (de qualquer sinal, em ordem crescente) em que a reta do raio
+    /// cruza a superfície do toro
+    pub(crate) fn line_roots(&self, ray: &Ray) -> Vec<f64> {
+        let big_r2 = self.major_radius * self.major_radius;
+        let small_r2 = self.minor_radius * self.minor_radius;
+        let bound = self.major_radius + self.minor_radius;
+
+        // Descarta os raios que não passam pela esfera que envolve o toro, e começa
+        // a resolver a equação a partir da entrada nela: com o p0 perto do toro,
+        // os coeficientes da quártica ficam menores e as raízes mais precisas
+        let oc = ray.p0 - self.center;
+        let Some([t_start, _]) = solve_quadratic(
+            ray.dr.length_squared(),
+            2.0 * ray.dr.dot(oc),
+            oc.length_squared() - bound * bound,
+        ) else {
+            return Vec::new();
+        };
+        let o = oc + ray.dr * t_start;
+        let d = ray.dr;
+
+        // Resolve (|P|² + R² - r²)² = 4R²(|P|² - (P•a)²), com P = o + t*d,
+        // ou seja (At² + Bt + C)² - 4R²(Dt² + Et + F) = 0
+        let od = o.dot(self.axis);
+        let dd = d.dot(self.axis);
+        let a = d.length_squared();
+        let b = 2.0 * o.dot(d);
+        let c = o.length_squared() + big_r2 - small_r2;
+        let e_2 = a - dd * dd;
+        let e_1 = 2.0 * (o.dot(d) - od * dd);
+        let e_0 = o.length_squared() - od * od;
+
+        solve_quartic(
+            a * a,
+            2.0 * a * b,
+            b * b + 2.0 * a * c - 4.0 * big_r2 * e_2,
+            2.0 * b * c - 4.0 * big_r2 * e_1,
+            c * c - 4.0 * big_r2 * e_0,
+        )
+        .into_iter()
+        .map(|t| t + t_start)
+        .collect()
+    }
+
+    /// Normal num ponto da superfície do toro: o vetor que sai do círculo central
+    /// do tubo em direção ao ponto
+    fn normal_at(&self, p: DVec3) -> DVec3 {
+        let cp = p - self.center;
+        // Ponto mais próximo de P no círculo de raio R que passa pelo meio do tubo
+        let ring_point = cp.reject_from_normalized(self.axis).normalize() * self.major_radius;
+        (cp - ring_point).normalize()
+    }
+}
+
+impl Shape for Torus {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
@@ -0,0 +1,112 @@
+use glam::DVec3;
+
+use crate::{Aabb, Intersection, Material, Ray, shapes::Shape};
+
+/// Triângulo definido por seus 3 vértices, com normais opcionais por vértice
+pub struct Triangle {
+    /// Vértices do triângulo (sentido anti-horário visto de frente)
+    pub vertices: [DVec3; 3],
+    /// Normais em cada vértice, interpoladas no ponto de interseção
+    /// (`None` usa a normal geométrica do triângulo)
+    pub normals: Option<[DVec3; 3]>,
+    /// Material do triângulo
+    pub material: Material,
+}
+
+impl Triangle {
+    /// Construtor do triângulo (sombreamento plano)
+    pub fn new(v0: DVec3, v1: DVec3, v2: DVec3, material: Material) -> Triangle {
+        Triangle {
+            vertices: [v0, v1, v2],
+            normals: None,
+            material,
+        }
+    }
+
+    /// Construtor do triângulo com uma normal por vértice (sombreamento suave)
+    pub fn with_normals(vertices: [DVec3; 3], normals: [DVec3; 3], material: Material) -> Triangle {
+        Triangle {
+            vertices,
+            normals: Some(normals.map(|n| n.normalize())),
+            material,
+        }
+    }
+
+    /// Caixa alinhada aos eixos que envolve o triângulo
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_points(self.vertices)
+    }
+
+    /// Retorna a interseção de um raio com o triângulo (`None` se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let [v0, v1, v2] = self.vertices;
+        let (t, barycentric) = triangle_intersects(ray, v0, v1, v2)?;
+        let geometric_normal = (v1 - v0).cross(v2 - v0).normalize();
+        let normal = match self.normals {
+            Some(normals) => interpolate_normal(normals, barycentric),
+            None => geometric_normal,
+        };
+        Some(Intersection {
+            t,
+            p: ray.at(t),
+            normal,
+            geometric_normal,
+            material: self.material,
+            barycentric: Some(barycentric),
+            object: self,
+        })
+    }
+}
+
+impl Shape for Triangle {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
+
+/// Teste de interseção raio-triângulo de Möller-Trumbore.
+/// Retorna o t da interseção e as coordenadas baricêntricas (w0, w1, w2)
+/// do ponto (`None` se não há interseção dentro do intervalo do raio).
+pub(crate) fn triangle_intersects(
+    ray: &Ray,
+    v0: DVec3,
+    v1: DVec3,
+    v2: DVec3,
+) -> Option<(f64, DVec3)> {
+    let e1 = v1 - v0;
+    let e2 = v2 - v0;
+    let pvec = ray.dr.cross(e2);
+    let det = e1.dot(pvec);
+    // Se o determinante é 0, o raio é paralelo ao plano do triângulo
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    let tvec = ray.p0 - v0;
+    let u = tvec.dot(pvec) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let qvec = tvec.cross(e1);
+    let v = ray.dr.dot(qvec) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = e2.dot(qvec) * inv_det;
+    if !ray.contains(t) {
+        return None;
+    }
+    Some((t, DVec3::new(1.0 - u - v, u, v)))
+}
+
+/// Interpola as normais dos vértices usando as coordenadas baricêntricas do ponto
+pub(crate) fn interpolate_normal(normals: [DVec3; 3], barycentric: DVec3) -> DVec3 {
+    (normals[0] * barycentric.x + normals[1] * barycentric.y + normals[2] * barycentric.z)
+        .normalize()
+}
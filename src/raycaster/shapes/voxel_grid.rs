@@ -0,0 +1,128 @@
+use glam::DVec3;
+
+use crate::{
+    Aabb, Intersection, Material, Ray,
+    shapes::{Shape, grid::GridTraversal},
+};
+
+/// Grade densa de voxels (cubos) alinhada aos eixos x, y e z. Cada voxel guarda um índice
+/// de material: 0 é vazio, e `i > 0` é um cubo sólido com o material `materials[i - 1]`.
+pub struct VoxelGrid {
+    /// Quina da grade com menor x, y e z
+    pub origin: DVec3,
+    /// Tamanho de cada voxel em cada eixo
+    pub voxel_size: DVec3,
+    /// Número de voxels em cada eixo
+    pub dimensions: [usize; 3],
+    /// Índices de material dos voxels (índice `x + nx * (y + ny * z)`)
+    pub voxels: Vec<u8>,
+    /// Materiais dos voxels sólidos
+    pub materials: Vec<Material>,
+}
+
+impl VoxelGrid {
+    /// Construtor da grade, com todos os voxels vazios
+    pub fn new(
+        origin: DVec3,
+        voxel_size: DVec3,
+        dimensions: [usize; 3],
+        materials: Vec<Material>,
+    ) -> VoxelGrid {
+        VoxelGrid {
+            origin,
+            voxel_size,
+            dimensions,
+            voxels: vec![0; dimensions.iter().product()],
+            materials,
+        }
+    }
+
+    /// Constrói a grade preenchendo cada voxel com o índice de material retornado
+    /// por `f(x, y, z)`
+    pub fn from_fn(
+        origin: DVec3,
+        voxel_size: DVec3,
+        dimensions: [usize; 3],
+        materials: Vec<Material>,
+        f: impl Fn(usize, usize, usize) -> u8,
+    ) -> VoxelGrid {
+        let mut grid = VoxelGrid::new(origin, voxel_size, dimensions, materials);
+        for z in 0..dimensions[2] {
+            for y in 0..dimensions[1] {
+                for x in 0..dimensions[0] {
+                    grid.set(x, y, z, f(x, y, z));
+                }
+            }
+        }
+        grid
+    }
+
+    /// Índice de material do voxel na posição (x, y, z) da grade
+    pub fn get(&self, x: usize, y: usize, z: usize) -> u8 {
+        self.voxels[self.index(x, y, z)]
+    }
+
+    /// Troca o índice de material do voxel na posição (x, y, z) da grade
+    pub fn set(&mut self, x: usize, y: usize, z: usize, material_index: u8) {
+        assert!(
+            material_index as usize <= self.materials.len(),
+            "índice de material {material_index} sem material correspondente"
+        );
+        let index = self.index(x, y, z);
+        self.voxels[index] = material_index;
+    }
+
+    /// Caixa alinhada aos eixos que envolve a grade
+    pub fn aabb(&self) -> Aabb {
+        let [x, y, z] = self.dimensions.map(|dimension| dimension as f64);
+        Aabb::from_points([
+            self.origin,
+            self.origin + self.voxel_size * DVec3::new(x, y, z),
+        ])
+    }
+
+    /// Retorna a interseção mais próxima de um raio com um voxel sólido: a primeira face
+    /// de voxel sólido por onde o raio entra dentro do seu intervalo (None se não há interseção)
+    pub fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        let traversal = GridTraversal::new(ray, self.origin, self.voxel_size, self.dimensions)?;
+        for cell in traversal {
+            let [x, y, z] = cell.index;
+            let material_index = self.get(x, y, z);
+            if material_index != 0 && ray.contains(cell.t_enter) {
+                // A normal é a da face por onde o raio entrou no voxel
+                let mut normal = DVec3::ZERO;
+                normal[cell.axis] = -ray.dr[cell.axis].signum();
+                return Some(Intersection {
+                    t: cell.t_enter,
+                    p: ray.at(cell.t_enter),
+                    normal,
+                    geometric_normal: normal,
+                    material: self.materials[material_index as usize - 1],
+                    barycentric: None,
+                    object: self,
+                });
+            }
+        }
+        None
+    }
+
+    /// Posição de um voxel no vetor `voxels`
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        let [nx, ny, nz] = self.dimensions;
+        assert!(
+            x < nx && y < ny && z < nz,
+            "voxel ({x}, {y}, {z}) fora da grade"
+        );
+        x + nx * (y + ny * z)
+    }
+}
+
+impl Shape for VoxelGrid {
+    fn intersects(&self, ray: &Ray) -> Option<Intersection<'_>> {
+        self.intersects(ray)
+    }
+
+    fn aabb(&self) -> Aabb {
+        self.aabb()
+    }
+}
@@ -0,0 +1,249 @@
+//! Resolução numérica das equações (polinomiais ou não) que aparecem nos testes de interseção.
+
+use std::array;
+
+use crate::{LaneMask, Lanes};
+
+/// Coeficientes menores que isso (em relação aos outros coeficientes do polinômio, no caso
+/// do de maior grau) são tratados como zero
+const EPSILON: f64 = 1e-12;
+/// Número máximo de divisões ao meio de um intervalo em `first_root`
+const MAX_SUBDIVISIONS: usize = 48;
+
+/// Resolve a equação do segundo grau a*t² + b*t + c = 0.
+/// Retorna as raízes reais em ordem crescente (`None` se não há raiz real).
+///
+/// Usa a forma q = -(b ± √Δ)/2, que evita a perda de precisão de (-b ± √Δ)/2a
+/// quando b² é muito maior que 4ac. Se `a` é desprezível perto de `b` e `c`, resolve a
+/// equação linear b*t + c = 0.
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Option<[f64; 2]> {
+    if is_negligible(a, &[b, c]) {
+        if is_negligible(b, &[c]) {
+            return None;
+        }
+        let t = -c / b;
+        return Some([t, t]);
+    }
+
+    let delta = b * b - 4.0 * a * c;
+    if delta < 0.0 {
+        return None;
+    }
+    let q = -0.5 * (b + b.signum() * delta.sqrt());
+    let t1 = q / a;
+    // Se q é zero, b e c também são, e a raiz é dupla em 0
+    let t2 = if q != 0.0 { c / q } else { t1 };
+    Some(if t1 < t2 { [t1, t2] } else { [t2, t1] })
+}
+
+/// `solve_quadratic` feita em cada raio de um pacote ao mesmo tempo (sem desvios, pra virar
+/// SIMD). Retorna as raízes em ordem crescente e em quais raios elas são reais.
+pub fn solve_quadratic_packet(a: Lanes, b: Lanes, c: Lanes) -> ([Lanes; 2], LaneMask) {
+    let delta = b * b - a * c * 4.0;
+    let q = (b + b.map(f64::signum) * delta.sqrt()) * -0.5;
+    let t1 = q / a;
+    // Se q é zero, b e c também são, e a raiz é dupla em 0
+    let t2 = Lanes::select(q.0.map(|q| q != 0.0), c / q, t1);
+    let real = delta.ge(0.0);
+
+    // Nos raios em que `a` é desprezível perto de `b` e `c`, a equação é linear
+    let linear = a.map(f64::abs).compare(
+        b.map(f64::abs).max(c.map(f64::abs)) * EPSILON,
+        |a, limit| a <= limit,
+    );
+    let linear_real = b
+        .map(f64::abs)
+        .compare(c.map(f64::abs) * EPSILON, |b, limit| b > limit);
+    let t = -c / b;
+    (
+        [t1.min(t2), t1.max(t2)].map(|root| Lanes::select(linear, t, root)),
+        array::from_fn(|lane| if linear[lane] { linear_real[lane] } else { real[lane] }),
+    )
+}
+
+/// Resolve a equação do terceiro grau a*t³ + b*t² + c*t + d = 0.
+/// Retorna as raízes reais em ordem crescente.
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if is_negligible(a, &[b, c, d]) {
+        return solve_quadratic(b, c, d).map_or_else(Vec::new, Vec::from);
+    }
+    let mut roots = solve_monic_cubic(b / a, c / a, d / a);
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Resolve a equação do quarto grau a*t⁴ + b*t³ + c*t² + d*t + e = 0.
+/// Retorna as raízes reais em ordem crescente.
+///
+/// Usa o método de Ferrari (fatorando a quártica em duas quadráticas com a ajuda
+/// de uma raiz da cúbica resolvente), e depois refina cada raiz com o método de
+/// Newton na equação original para corrigir o erro acumulado.
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if is_negligible(a, &[b, c, d, e]) {
+        return solve_cubic(b, c, d, e);
+    }
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+
+    // Quártica reduzida y⁴ + p*y² + q*y + r = 0, com t = y - b/4
+    let b2 = b * b;
+    let p = c - 3.0 * b2 / 8.0;
+    let q = d - b * c / 2.0 + b2 * b / 8.0;
+    let r = e - b * d / 4.0 + b2 * c / 16.0 - 3.0 * b2 * b2 / 256.0;
+
+    let mut roots = Vec::with_capacity(4);
+    if q.abs() < EPSILON {
+        // Biquadrada: z = y², z² + p*z + r = 0
+        for z in solve_quadratic(1.0, p, r).into_iter().flatten() {
+            if z >= 0.0 {
+                roots.push(z.sqrt());
+                roots.push(-z.sqrt());
+            }
+        }
+    } else {
+        // Maior raiz real m da cúbica resolvente m³ + p*m² + (p²/4 - r)*m - q²/8 = 0
+        // (que é sempre positiva quando q != 0)
+        let m = solve_monic_cubic(p, p * p / 4.0 - r, -q * q / 8.0)
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max);
+        if m <= 0.0 {
+            return Vec::new();
+        }
+        // y⁴ + p*y² + q*y + r = (y² - s*y + p/2 + m + q/2s) * (y² + s*y + p/2 + m - q/2s)
+        let s = (2.0 * m).sqrt();
+        let k = q / (2.0 * s);
+        for (linear, constant) in [(-s, p / 2.0 + m + k), (s, p / 2.0 + m - k)] {
+            if let Some([y1, y2]) = solve_quadratic(1.0, linear, constant) {
+                roots.push(y1);
+                roots.push(y2);
+            }
+        }
+    }
+
+    // Volta pra variável t e refina as raízes na equação original
+    let mut roots: Vec<f64> = roots
+        .into_iter()
+        .map(|y| polish_root(&[1.0, b, c, d, e], y - b / 4.0))
+        .collect();
+    roots.sort_by(f64::total_cmp);
+    roots
+}
+
+/// Diz se o coeficiente de maior grau de um polinômio é desprezível perto dos outros
+/// coeficientes, caso em que o polinômio é tratado como sendo de um grau a menos.
+/// A comparação é relativa, pra não depender da escala da equação (multiplicar todos os
+/// coeficientes por um número não muda as raízes).
+fn is_negligible(leading: f64, others: &[f64]) -> bool {
+    let largest = others
+        .iter()
+        .fold(0.0, |largest: f64, k| largest.max(k.abs()));
+    leading.abs() <= EPSILON * largest
+}
+
+/// Resolve t³ + a*t² + b*t + c = 0 (método trigonométrico de Viète quando há
+/// 3 raízes reais, Cardano quando há só uma). Raízes em ordem qualquer.
+fn solve_monic_cubic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    let q = (a * a - 3.0 * b) / 9.0;
+    let r = (2.0 * a * a * a - 9.0 * a * b + 27.0 * c) / 54.0;
+    let q3 = q * q * q;
+
+    if r * r < q3 {
+        let theta = (r / q3.sqrt()).clamp(-1.0, 1.0).acos();
+        let sq = -2.0 * q.sqrt();
+        let tau = std::f64::consts::TAU;
+        vec![
+            sq * (theta / 3.0).cos() - a / 3.0,
+            sq * ((theta + tau) / 3.0).cos() - a / 3.0,
+            sq * ((theta - tau) / 3.0).cos() - a / 3.0,
+        ]
+    } else {
+        let big_a = -r.signum() * (r.abs() + (r * r - q3).sqrt()).cbrt();
+        let big_b = if big_a != 0.0 { q / big_a } else { 0.0 };
+        vec![big_a + big_b - a / 3.0]
+    }
+}
+
+/// Refina uma raiz aproximada de um polinômio (coeficientes do maior grau pro menor)
+/// com algumas iterações do método de Newton, parando se o resíduo deixar de diminuir
+fn polish_root(coefficients: &[f64], t: f64) -> f64 {
+    // Avalia o polinômio e sua derivada pelo método de Horner
+    let evaluate = |t: f64| {
+        coefficients
+            .iter()
+            .fold((0.0, 0.0), |(value, derivative), &k| {
+                (value * t + k, derivative * t + value)
+            })
+    };
+
+    let mut t = t;
+    let (mut value, mut derivative) = evaluate(t);
+    for _ in 0..4 {
+        if derivative.abs() < EPSILON {
+            break;
+        }
+        let next = t - value / derivative;
+        let (next_value, next_derivative) = evaluate(next);
+        if next_value.abs() >= value.abs() {
+            break;
+        }
+        (t, value, derivative) = (next, next_value, next_derivative);
+    }
+    t
+}
+
+/// Acha a primeira raiz (a de menor t) de f no intervalo `[t0, t1]`.
+///
+/// `bounds(a, b)` deve retornar limites inferior e superior de f em `[a, b]` (eles não
+/// precisam ser justos, mas não podem errar). O intervalo é dividido ao meio recursivamente,
+/// descartando os pedaços cujos limites não contêm o zero, até os pedaços terem tamanho
+/// `tolerance`. Nesses pedaços, uma troca de sinal de f é refinada por bissecção.
+/// Raízes em que f só encosta no zero sem trocar de sinal podem não ser encontradas.
+pub fn first_root(
+    f: impl Fn(f64) -> f64,
+    bounds: impl Fn(f64, f64) -> (f64, f64),
+    [t0, t1]: [f64; 2],
+    tolerance: f64,
+) -> Option<f64> {
+    first_root_in(&f, &bounds, t0, t1, tolerance, MAX_SUBDIVISIONS)
+}
+
+fn first_root_in(
+    f: &dyn Fn(f64) -> f64,
+    bounds: &dyn Fn(f64, f64) -> (f64, f64),
+    a: f64,
+    b: f64,
+    tolerance: f64,
+    depth: usize,
+) -> Option<f64> {
+    let (low, high) = bounds(a, b);
+    if low > 0.0 || high < 0.0 {
+        return None;
+    }
+
+    if b - a > tolerance && depth > 0 {
+        // Procura primeiro na metade de menor t
+        let mid = (a + b) / 2.0;
+        return first_root_in(f, bounds, a, mid, tolerance, depth - 1)
+            .or_else(|| first_root_in(f, bounds, mid, b, tolerance, depth - 1));
+    }
+
+    let (mut a, mut b) = (a, b);
+    let fa = f(a);
+    if fa == 0.0 {
+        return Some(a);
+    }
+    if fa.signum() == f(b).signum() {
+        return None;
+    }
+    for _ in 0..64 {
+        let mid = (a + b) / 2.0;
+        if mid <= a || mid >= b {
+            break;
+        }
+        if f(mid).signum() == fa.signum() {
+            a = mid;
+        } else {
+            b = mid;
+        }
+    }
+    Some((a + b) / 2.0)
+}
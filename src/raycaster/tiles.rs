@@ -0,0 +1,196 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+/// Ordem em que os blocos de um frame são renderizados
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TileOrder {
+    /// Linha por linha, de cima pra baixo e da esquerda pra direita
+    Rows,
+    /// Em espiral, do bloco do centro da imagem pra fora (o centro aparece primeiro)
+    Spiral,
+    /// Pela curva de Hilbert, que passa por blocos vizinhos em sequência
+    /// (os blocos renderizados juntos ficam próximos na imagem e na cena)
+    Hilbert,
+}
+
+/// Bloco retangular de pixels de um frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tile {
+    /// Coluna do pixel superior-esquerdo do bloco
+    pub x: usize,
+    /// Linha do pixel superior-esquerdo do bloco
+    pub y: usize,
+    /// Largura do bloco em pixels
+    pub width: usize,
+    /// Altura do bloco em pixels
+    pub height: usize,
+}
+
+/// Divide frames em blocos quadrados e define a ordem em que eles são renderizados
+#[derive(Clone, Copy, Debug)]
+pub struct TileScheduler {
+    /// Lado dos blocos em pixels (os da borda direita e de baixo podem ser menores)
+    pub tile_size: usize,
+    /// Ordem dos blocos
+    pub order: TileOrder,
+}
+
+/// Bloco terminado durante uma renderização por blocos (ver `Camera::render_tiles`)
+pub struct TileProgress<'a> {
+    /// Bloco terminado
+    pub tile: Tile,
+    /// Pixels RGB do bloco, linha por linha (`tile.width * 3` bytes por linha)
+    pub pixels: &'a [u8],
+    /// Número de blocos do frame terminados até agora, contando este
+    pub finished: usize,
+    /// Número total de blocos do frame
+    pub total: usize,
+}
+
+/// Sinal pra interromper uma renderização em andamento (em outra thread, por exemplo).
+/// As cópias do token compartilham o mesmo sinal.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TileScheduler {
+    /// Construtor do escalonador de blocos
+    pub fn new(tile_size: usize, order: TileOrder) -> TileScheduler {
+        assert!(tile_size > 0, "o tamanho dos blocos deve ser positivo");
+        TileScheduler { tile_size, order }
+    }
+
+    /// Blocos que cobrem um frame de `width` x `height` pixels, na ordem de renderização
+    pub fn tiles(&self, width: usize, height: usize) -> Vec<Tile> {
+        let columns = width.div_ceil(self.tile_size);
+        let rows = height.div_ceil(self.tile_size);
+        let cells = match self.order {
+            TileOrder::Rows => (0..rows)
+                .flat_map(|row| (0..columns).map(move |column| (column, row)))
+                .collect(),
+            TileOrder::Spiral => spiral(columns, rows),
+            TileOrder::Hilbert => hilbert(columns, rows),
+        };
+        cells
+            .into_iter()
+            .map(|(column, row)| {
+                let x = column * self.tile_size;
+                let y = row * self.tile_size;
+                Tile {
+                    x,
+                    y,
+                    width: self.tile_size.min(width - x),
+                    height: self.tile_size.min(height - y),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for TileScheduler {
+    /// Blocos de 32x32 pixels em espiral
+    fn default() -> TileScheduler {
+        TileScheduler::new(32, TileOrder::Spiral)
+    }
+}
+
+impl TileProgress<'_> {
+    /// Copia os pixels do bloco pro lugar dele num frame RGB com `frame_width` pixels de largura
+    pub fn copy_to(&self, frame: &mut [u8], frame_width: usize) {
+        let row_length = self.tile.width * 3;
+        for (row, pixels) in self.pixels.chunks_exact(row_length).enumerate() {
+            let start = ((self.tile.y + row) * frame_width + self.tile.x) * 3;
+            frame[start..start + row_length].copy_from_slice(pixels);
+        }
+    }
+}
+
+impl CancellationToken {
+    /// Construtor do token, ainda não cancelado
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Pede a interrupção das renderizações que usam o token
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Diz se a interrupção já foi pedida
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Células de uma grade `columns` x `rows` numa espiral quadrada que começa na célula
+/// do centro e vai girando pra fora (pulando as voltas que saem da grade)
+fn spiral(columns: usize, rows: usize) -> Vec<(usize, usize)> {
+    let total = columns * rows;
+    let mut cells = Vec::with_capacity(total);
+    if total == 0 {
+        return cells;
+    }
+    let (mut x, mut y) = ((columns as isize - 1) / 2, (rows as isize - 1) / 2);
+    let visit = |x: isize, y: isize, cells: &mut Vec<(usize, usize)>| {
+        if (0..columns as isize).contains(&x) && (0..rows as isize).contains(&y) {
+            cells.push((x as usize, y as usize));
+        }
+    };
+    visit(x, y, &mut cells);
+
+    // Direita, baixo, esquerda, cima: cada lado da espiral anda uma célula a mais
+    // a cada duas viradas
+    let directions = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+    let mut length = 1;
+    let mut direction = 0;
+    while cells.len() < total {
+        for _ in 0..2 {
+            let (dx, dy) = directions[direction % 4];
+            for _ in 0..length {
+                x += dx;
+                y += dy;
+                visit(x, y, &mut cells);
+            }
+            direction += 1;
+        }
+        length += 1;
+    }
+    cells
+}
+
+/// Células de uma grade `columns` x `rows` na ordem da curva de Hilbert do menor
+/// quadrado de lado potência de 2 que contém a grade (pulando as que ficam fora dela)
+fn hilbert(columns: usize, rows: usize) -> Vec<(usize, usize)> {
+    let side = columns.max(rows).next_power_of_two();
+    (0..side * side)
+        .map(|d| hilbert_cell(side, d))
+        .filter(|&(x, y)| x < columns && y < rows)
+        .collect()
+}
+
+/// Célula de índice d na curva de Hilbert de um quadrado com `side` células de lado
+fn hilbert_cell(side: usize, d: usize) -> (usize, usize) {
+    let (mut x, mut y) = (0, 0);
+    let mut t = d;
+    let mut s = 1;
+    // Sobe de quadrante em quadrante, girando/espelhando a posição dentro de cada um
+    while s < side {
+        let rx = 1 & (t / 2);
+        let ry = 1 & (t ^ rx);
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - x;
+                y = s - 1 - y;
+            }
+            (x, y) = (y, x);
+        }
+        x += s * rx;
+        y += s * ry;
+        t /= 4;
+        s *= 2;
+    }
+    (x, y)
+}
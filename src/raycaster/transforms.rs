@@ -1,4 +1,4 @@
-use glam::{DVec3, DMat4};
+use glam::{DMat3, DMat4, DVec3};
 
 /// Creates a rotation matrix (DMat4) that rotates around a given axis by a given angle (in radians).
 pub fn rotation_matrix_from_axis_angle(axis: DVec3, angle_rad: f64) -> DMat4 {
@@ -27,3 +27,30 @@ pub fn rotation_matrix_from_axis_angle(axis: DVec3, angle_rad: f64) -> DMat4 {
         0.0, 0.0, 0.0, 1.0,
     ])
 }
+
+/// Creates a translation matrix (DMat4) that moves points by the given offset.
+pub fn translation_matrix(offset: DVec3) -> DMat4 {
+    DMat4::from_cols_array(&[
+        1.0, 0.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        offset.x, offset.y, offset.z, 1.0,
+    ])
+}
+
+/// Creates a scaling matrix (DMat4) that scales each axis by the given factor.
+pub fn scaling_matrix(factors: DVec3) -> DMat4 {
+    DMat4::from_cols_array(&[
+        factors.x, 0.0, 0.0, 0.0,
+        0.0, factors.y, 0.0, 0.0,
+        0.0, 0.0, factors.z, 0.0,
+        0.0, 0.0, 0.0, 1.0,
+    ])
+}
+
+/// Returns the matrix (DMat3) that transforms normal vectors under an affine transform:
+/// the inverse-transpose of its linear part, which keeps normals perpendicular to
+/// surfaces even under non-uniform scaling.
+pub fn normal_matrix(transform: DMat4) -> DMat3 {
+    DMat3::from_mat4(transform).inverse().transpose()
+}
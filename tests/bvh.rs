@@ -0,0 +1,119 @@
+//! A BVH deve achar as mesmas interseções que testar todos os objetos um por um.
+
+use glam::DVec3;
+use raycaster::{
+    Aabb, Bvh, Material, Ray,
+    shapes::{Cilinder, Plane, Shape, Sphere},
+};
+
+/// Gerador de números pseudoaleatórios entre 0 e 1 (congruencial linear), pra que os
+/// testes sejam reproduzíveis
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    fn vector(&mut self, scale: f64) -> DVec3 {
+        DVec3::new(self.next() - 0.5, self.next() - 0.5, self.next() - 0.5) * 2.0 * scale
+    }
+}
+
+/// Objetos espalhados numa caixa, com aglomerados de objetos repetidos (caixas iguais não
+/// podem ser separadas por nenhum plano) e um plano, que fica fora da árvore
+fn objects(random: &mut Random) -> Vec<Box<dyn Shape>> {
+    let material = Material::WHITE;
+    let mut objects: Vec<Box<dyn Shape>> = Vec::new();
+    for i in 0..300 {
+        let center = random.vector(10.0);
+        objects.push(match i % 3 {
+            0 => Box::new(Sphere::new(center, 0.1 + random.next(), material)),
+            1 => Box::new(Cilinder::new(
+                center,
+                random.vector(1.0).normalize(),
+                1.0,
+                0.2,
+                true,
+                true,
+                material,
+            )),
+            _ => {
+                let center = DVec3::new(3.0, -2.0, 1.0);
+                Box::new(Sphere::new(center, 0.5, material))
+            }
+        });
+    }
+    objects.push(Box::new(Plane::new(
+        DVec3::new(0.0, -12.0, 0.0),
+        DVec3::Y,
+        material,
+    )));
+    objects
+}
+
+/// Interseção mais próxima testando todos os objetos
+fn linear_closest_hit(objects: &[Box<dyn Shape>], ray: &Ray) -> Option<f64> {
+    objects
+        .iter()
+        .filter_map(|object| object.intersects(ray))
+        .map(|intersection| intersection.t)
+        .reduce(f64::min)
+}
+
+#[test]
+fn closest_hit_matches_a_linear_scan() {
+    let mut random = Random(4);
+    let objects = objects(&mut random);
+    let bounds: Vec<Aabb> = objects.iter().map(|object| object.aabb()).collect();
+    let bvh = Bvh::new(&bounds);
+    assert_eq!(bvh.len(), objects.len());
+
+    let mut tested = 0;
+    let ray_count = 2000;
+    for i in 0..ray_count {
+        let p0 = random.vector(15.0);
+        let ray = match i % 4 {
+            // Raios com o intervalo recortado e raios saindo de dentro da cena
+            0 => Ray::new(p0, random.vector(5.0) - p0).with_interval(0.2, 0.6),
+            1 => Ray::new(random.vector(5.0), random.vector(1.0)),
+            _ => Ray::new(p0, random.vector(5.0) - p0),
+        };
+        let hit = bvh
+            .closest_hit(&ray, |i| {
+                tested += 1;
+                objects[i].intersects(&ray)
+            })
+            .map(|intersection| intersection.t);
+        assert_eq!(hit, linear_closest_hit(&objects, &ray));
+        assert_eq!(
+            bvh.any_hit(&ray, |i| objects[i].intersects(&ray).is_some()),
+            hit.is_some()
+        );
+    }
+    // A árvore tem que descartar a maior parte dos objetos
+    assert!(tested < ray_count * objects.len() / 4, "{tested} testes");
+}
+
+#[test]
+fn empty_and_unbounded_objects() {
+    let ray = Ray::new(DVec3::new(0.0, 5.0, 0.0), -DVec3::Y);
+    let plane = Plane::new(DVec3::ZERO, DVec3::Y, Material::WHITE);
+
+    // Objetos com caixa vazia nunca são testados
+    let bvh = Bvh::new(&[Aabb::EMPTY, Aabb::INFINITE]);
+    let hit = bvh.closest_hit(&ray, |i| {
+        assert_eq!(i, 1);
+        plane.intersects(&ray)
+    });
+    assert_eq!(hit.map(|intersection| intersection.t), Some(5.0));
+
+    let bvh = Bvh::new(&[]);
+    assert!(bvh.is_empty());
+    assert!(bvh.closest_hit(&ray, |_| unreachable!()).is_none());
+    assert!(!bvh.any_hit(&ray, |_| unreachable!()));
+}
@@ -0,0 +1,138 @@
+//! Leitura de arquivos .obj e .mtl a partir de textos no próprio teste.
+
+use std::collections::HashMap;
+
+use glam::DVec3;
+use raycaster::{
+    Material, Ray,
+    loaders::{LoadError, parse_mtl, parse_obj},
+};
+
+const MTL: &str = "
+# Dois materiais
+newmtl vermelho
+Ka 0.1 0 0
+Kd 0.9 0.1 0.1
+Ks 0.5
+Ns 40
+
+newmtl azul
+Kd 0 0 1
+";
+
+#[test]
+fn polygons_are_split_into_triangles() {
+    let source = "
+        v 0 0 0
+        v 1 0 0
+        v 1 1 0
+        v 0 1 0
+        v 0.5 1.5 0 # comentário no fim da linha
+        f 1 2 3 5 4
+    ";
+    let meshes = parse_obj(source, &HashMap::new()).unwrap();
+    assert_eq!(meshes.len(), 1);
+    let mesh = &meshes[0];
+    assert_eq!(mesh.vertices().len(), 5);
+    assert_eq!(mesh.faces(), &[[0, 1, 2], [0, 2, 3], [0, 3, 4]]);
+
+    // O polígono inteiro é atingido, e só pela frente da caixa que o contém
+    let ray = Ray::new(DVec3::new(0.5, 1.2, 1.0), -DVec3::Z);
+    let intersection = mesh.intersects(&ray).unwrap();
+    assert!((intersection.t - 1.0).abs() < 1e-12);
+    assert!(
+        mesh.intersects(&Ray::new(DVec3::new(1.5, 0.5, 1.0), -DVec3::Z))
+            .is_none()
+    );
+}
+
+#[test]
+fn indices_can_be_relative_and_carry_normals() {
+    let source = "
+        v 0 0 0
+        v 1 0 0
+        v 0 1 0
+        vt 0 0
+        vn 0 0 2
+        f -3/1/1 -2/1/1 -1//1
+    ";
+    let meshes = parse_obj(source, &HashMap::new()).unwrap();
+    let mesh = &meshes[0];
+    assert_eq!(mesh.faces(), &[[0, 1, 2]]);
+    // As normais do arquivo são usadas (normalizadas) no lugar das calculadas
+    for normal in &mesh.normals {
+        assert!((*normal - DVec3::Z).length() < 1e-12);
+    }
+}
+
+#[test]
+fn groups_and_materials_make_separate_meshes() {
+    let materials = parse_mtl(MTL).unwrap();
+    assert_eq!(materials.len(), 2);
+    let red = materials["vermelho"];
+    assert_eq!(red.k_amb, DVec3::new(0.1, 0.0, 0.0));
+    assert_eq!(red.k_esp, DVec3::splat(0.5));
+    assert_eq!(red.e, 40.0);
+
+    let source = "
+        v 0 0 0
+        v 1 0 0
+        v 0 1 0
+        v 0 0 1
+        g tampa
+        usemtl vermelho
+        f 1 2 3
+        usemtl azul
+        f 1 2 4
+        g fundo
+        usemtl vermelho
+        f 1 3 4
+        g tampa
+        usemtl vermelho
+        f 2 3 4
+        usemtl inexistente
+        f 1 2 3
+    ";
+    let meshes = parse_obj(source, &materials).unwrap();
+    // (tampa, vermelho), (tampa, azul), (fundo, vermelho), (tampa, inexistente)
+    assert_eq!(meshes.len(), 4);
+    assert_eq!(meshes[0].faces().len(), 2);
+    assert_eq!(meshes[0].material.k_dif, DVec3::new(0.9, 0.1, 0.1));
+    assert_eq!(meshes[1].material.k_dif, DVec3::Z);
+    assert_eq!(meshes[2].faces().len(), 1);
+    // Um material que não está nos .mtl vira o material padrão
+    assert_eq!(meshes[3].material.k_dif, Material::WHITE.k_dif);
+}
+
+#[test]
+fn errors_point_to_the_line() {
+    let error = |source: &str| parse_obj(source, &HashMap::new()).err().unwrap();
+
+    assert!(matches!(
+        error("v 0 0 0\nv 1 x 0\n"),
+        LoadError::Parse { line: 2, .. }
+    ));
+    // Índices fora do intervalo (o 0 não existe no .obj)
+    assert!(matches!(
+        error("v 0 0 0\nv 1 0 0\nv 0 1 0\n\nf 1 2 4\n"),
+        LoadError::Parse { line: 5, .. }
+    ));
+    assert!(matches!(
+        error("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2\n"),
+        LoadError::Parse { line: 4, .. }
+    ));
+    // Normal que não existe e face com menos de 3 vértices
+    assert!(matches!(
+        error("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1//1 2//1 3//1\n"),
+        LoadError::Parse { line: 4, .. }
+    ));
+    assert!(matches!(
+        error("v 0 0 0\nv 1 0 0\nf 1 2\n"),
+        LoadError::Parse { line: 3, .. }
+    ));
+
+    assert!(matches!(
+        parse_mtl("Kd 1 1 1\n").err().unwrap(),
+        LoadError::Parse { line: 1, .. }
+    ));
+}
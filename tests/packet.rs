@@ -0,0 +1,187 @@
+//! Os testes com pacotes de raios devem dar os mesmos resultados que os raios sozinhos.
+
+use glam::DVec3;
+use raycaster::{
+    Aabb, Material, PACKET_SIZE, Ray, RayPacket, Scene,
+    shapes::{Cilinder, Cone, Plane, Shape, Sphere},
+};
+
+/// Gerador de números pseudoaleatórios entre 0 e 1 (congruencial linear), pra que os
+/// testes sejam reproduzíveis
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1_u64 << 53) as f64
+    }
+
+    fn vector(&mut self, scale: f64) -> DVec3 {
+        DVec3::new(self.next() - 0.5, self.next() - 0.5, self.next() - 0.5) * 2.0 * scale
+    }
+}
+
+/// Raios saindo de pontos ao redor da origem em direção a pontos perto dela
+fn rays(random: &mut Random, count: usize) -> Vec<Ray> {
+    (0..count)
+        .map(|_| {
+            let p0 = random.vector(6.0);
+            Ray::new(p0, random.vector(2.0) - p0)
+        })
+        .collect()
+}
+
+/// t da interseção de um raio sozinho (infinito se não há)
+fn scalar_t(shape: &dyn Shape, ray: &Ray) -> f64 {
+    shape
+        .intersects(ray)
+        .map_or(f64::INFINITY, |intersection| intersection.t)
+}
+
+fn assert_same_t(packet_t: f64, scalar_t: f64) {
+    assert!(
+        packet_t == scalar_t || (packet_t - scalar_t).abs() <= 1e-9 * scalar_t.abs().max(1.0),
+        "pacote: {packet_t}, raio sozinho: {scalar_t}"
+    );
+}
+
+#[test]
+fn shapes_match_single_rays() {
+    let material = Material::WHITE;
+    let axis = DVec3::new(0.3, 1.0, -0.4).normalize();
+    let base = DVec3::new(0.0, -1.0, 0.0);
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Sphere::new(DVec3::new(0.2, 0.1, 0.3), 1.3, material)),
+        Box::new(Plane::new(
+            base,
+            DVec3::new(0.1, 1.0, 0.2).normalize(),
+            material,
+        )),
+        Box::new(Cilinder::new(base, axis, 2.0, 0.8, true, true, material)),
+        Box::new(Cilinder::new(base, axis, 2.0, 0.8, false, true, material)),
+        Box::new(Cone::new(base, axis, 2.0, 0.8, true, material)),
+        Box::new(Cone::new(base, axis, 2.0, 1.5, false, material)),
+    ];
+
+    let mut random = Random(1);
+    for shape in &shapes {
+        let mut hits = 0;
+        for (i, group) in rays(&mut random, 400).chunks(PACKET_SIZE).enumerate() {
+            // Pacotes cheios e incompletos, com o intervalo padrão e com intervalos recortados
+            for count in 1..=PACKET_SIZE {
+                let group: Vec<Ray> = group[..count]
+                    .iter()
+                    .map(|ray| match i % 3 {
+                        0 => Ray::new(ray.p0, ray.dr),
+                        1 => Ray::new(ray.p0, ray.dr).with_interval(0.3, 0.7),
+                        _ => Ray::new(ray.at(0.5), ray.dr),
+                    })
+                    .collect();
+                let t = shape.intersects_packet(&RayPacket::new(&group));
+                for lane in 0..PACKET_SIZE {
+                    let expected = group
+                        .get(lane)
+                        .map_or(f64::INFINITY, |ray| scalar_t(shape.as_ref(), ray));
+                    hits += expected.is_finite() as usize;
+                    assert_same_t(t.0[lane], expected);
+                }
+            }
+        }
+        assert!(hits > 0);
+    }
+}
+
+#[test]
+fn scene_matches_single_rays() {
+    let material = Material::RED;
+    let mut random = Random(2);
+    let mut objects: Vec<Box<dyn Shape>> = Vec::new();
+    for i in 0..40 {
+        let center = random.vector(5.0);
+        let axis = random.vector(1.0).normalize();
+        objects.push(match i % 3 {
+            0 => Box::new(Sphere::new(center, 0.6, material)),
+            1 => Box::new(Cilinder::new(center, axis, 1.0, 0.3, true, true, material)),
+            _ => Box::new(Cone::new(center, axis, 1.2, 0.5, true, material)),
+        });
+    }
+    objects.push(Box::new(Plane::new(
+        DVec3::new(0.0, -6.0, 0.0),
+        DVec3::Y,
+        material,
+    )));
+    let mut scene = Scene::new(objects, Vec::new(), DVec3::ZERO);
+    // Objeto fora da BVH, testado um por um
+    scene.push(Box::new(Sphere::new(DVec3::ZERO, 0.8, material)));
+
+    let check = |rays: &[Ray]| {
+        let intersections = scene.intersects_packet(&RayPacket::new(rays));
+        for (lane, intersection) in intersections.iter().enumerate() {
+            let expected = rays.get(lane).and_then(|ray| scene.intersects(ray));
+            assert_eq!(
+                intersection.as_ref().map(|intersection| intersection.t),
+                expected.map(|intersection| intersection.t)
+            );
+        }
+    };
+
+    // Raios primários de uma câmera, em pacotes coerentes cheios e incompletos
+    let p0 = DVec3::new(0.0, 1.0, 12.0);
+    for y in 0..40 {
+        for x in (0..60).step_by(PACKET_SIZE) {
+            let rays: Vec<Ray> = (0..PACKET_SIZE)
+                .map(|k| {
+                    let target =
+                        DVec3::new((x + k) as f64 / 60.0 - 0.5, 0.5 - y as f64 / 40.0, -1.0);
+                    Ray::new(p0, target.normalize())
+                })
+                .collect();
+            for count in 1..=PACKET_SIZE {
+                check(&rays[..count]);
+            }
+        }
+    }
+
+    // Pacotes incoerentes (direções em octantes diferentes) são testados raio a raio
+    for rays in rays(&mut random, 200).chunks(PACKET_SIZE) {
+        check(rays);
+    }
+    let incoherent = [
+        Ray::new(p0, -DVec3::Z),
+        Ray::new(p0, DVec3::new(0.1, -0.2, 1.0).normalize()),
+    ];
+    assert!(!RayPacket::new(&incoherent).is_coherent());
+    check(&incoherent);
+}
+
+#[test]
+fn aabb_packet_entry_is_the_closest_of_the_single_rays() {
+    let aabb = Aabb::new(DVec3::new(-1.0, -0.5, -2.0), DVec3::new(1.5, 0.5, 1.0));
+    let mut random = Random(3);
+    let mut rays = rays(&mut random, 400);
+    // Raios paralelos a planos da caixa, dentro e fora da faixa dela nesse eixo
+    rays.extend([
+        Ray::new(DVec3::new(-5.0, 0.0, 0.0), DVec3::X),
+        Ray::new(DVec3::new(-5.0, 2.0, 0.0), DVec3::X),
+        Ray::new(DVec3::new(0.0, 0.0, 5.0), -DVec3::Z),
+        Ray::new(DVec3::ZERO, DVec3::Y),
+        Ray::new(DVec3::new(-5.0, 0.0, 0.0), DVec3::X).with_interval(0.0, 2.0),
+    ]);
+
+    for group in rays.chunks(PACKET_SIZE) {
+        for count in 1..=group.len() {
+            let expected = group[..count]
+                .iter()
+                .filter_map(|ray| aabb.intersects(ray))
+                .map(|(t_enter, _)| t_enter)
+                .reduce(f64::min);
+            assert_eq!(
+                aabb.intersects_packet(&RayPacket::new(&group[..count])),
+                expected
+            );
+        }
+    }
+}
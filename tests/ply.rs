@@ -0,0 +1,136 @@
+//! Leitura de arquivos .ply (ASCII e binários) a partir de conteúdos montados no próprio teste.
+
+use glam::DVec3;
+use raycaster::{
+    Material,
+    loaders::{LoadError, parse_ply, parse_ply_points},
+};
+
+/// Quadrado no plano z = 0, com uma face de 4 vértices, normais e cores
+const ASCII: &str = "ply
+format ascii 1.0
+comment quadrado
+element vertex 4
+property float x
+property float y
+property float z
+property float nx
+property float ny
+property float nz
+property uchar red
+property uchar green
+property uchar blue
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0 0 0 1 255 0 0
+1 0 0 0 0 1 0 255 0
+1 1 0 0 0 1 0 0 255
+0 1 0 0 0 1 255 255 255
+4 0 1 2 3
+";
+
+/// Cabeçalho do mesmo quadrado de `ASCII` em formato binário (sem normais nem cores)
+fn binary_header(format: &str) -> Vec<u8> {
+    format!(
+        "ply\nformat {format} 1.0\nelement vertex 4\nproperty float x\nproperty float y\n\
+         property float z\nelement face 1\nproperty list uchar int vertex_indices\nend_header\n"
+    )
+    .into_bytes()
+}
+
+/// Corpo binário do quadrado, com os valores convertidos em bytes por `f32_bytes` e `i32_bytes`
+fn binary_body(f32_bytes: fn(f32) -> [u8; 4], i32_bytes: fn(i32) -> [u8; 4]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for [x, y] in [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]] {
+        for value in [x, y, 0.0] {
+            body.extend(f32_bytes(value));
+        }
+    }
+    body.push(4);
+    for index in 0..4 {
+        body.extend(i32_bytes(index));
+    }
+    body
+}
+
+#[test]
+fn ascii_mesh_with_normals_and_colors() {
+    let mesh = parse_ply(ASCII.as_bytes(), Material::WHITE).unwrap();
+    assert_eq!(mesh.vertices()[2], DVec3::new(1.0, 1.0, 0.0));
+    assert_eq!(mesh.faces(), &[[0, 1, 2], [0, 2, 3]]);
+    assert!(mesh.normals.iter().all(|&normal| normal == DVec3::Z));
+    // Cores uchar vão de 0 a 1
+    let colors = mesh.colors.as_ref().unwrap();
+    assert_eq!(colors[0], DVec3::X);
+    assert_eq!(colors[3], DVec3::ONE);
+}
+
+#[test]
+fn binary_formats_match_ascii() {
+    let ascii = parse_ply(ASCII.as_bytes(), Material::WHITE).unwrap();
+    for (format, body) in [
+        (
+            "binary_little_endian",
+            binary_body(f32::to_le_bytes, i32::to_le_bytes),
+        ),
+        (
+            "binary_big_endian",
+            binary_body(f32::to_be_bytes, i32::to_be_bytes),
+        ),
+    ] {
+        let mut bytes = binary_header(format);
+        bytes.extend(body);
+        let mesh = parse_ply(&bytes, Material::WHITE).unwrap();
+        assert_eq!(mesh.vertices(), ascii.vertices());
+        assert_eq!(mesh.faces(), ascii.faces());
+        // Sem normais no arquivo, elas são calculadas pela ordem dos vértices
+        assert!(mesh.normals.iter().all(|&normal| normal == DVec3::Z));
+        assert!(mesh.colors.is_none());
+    }
+}
+
+#[test]
+fn point_cloud_uses_only_the_vertices() {
+    let cloud = parse_ply_points(ASCII.as_bytes(), 0.1, Material::WHITE).unwrap();
+    assert_eq!(cloud.points().len(), 4);
+    assert_eq!(cloud.normals().unwrap()[1], DVec3::Z);
+    assert_eq!(cloud.colors().unwrap()[1], DVec3::Y);
+    assert_eq!(cloud.radius(), 0.1);
+}
+
+#[test]
+fn malformed_files_are_errors() {
+    let error = |source: &str| parse_ply(source.as_bytes(), Material::WHITE).err().unwrap();
+
+    assert!(matches!(
+        error("obj\nformat ascii 1.0\nend_header\n"),
+        LoadError::Parse { line: 1, .. }
+    ));
+    assert!(matches!(
+        error("ply\nformat ascii 1.0\nelement vertex 1\n"),
+        LoadError::UnexpectedEof { .. }
+    ));
+    assert!(matches!(
+        error("ply\nformat ascii 1.0\nelement vertex 1\nproperty real x\nend_header\n"),
+        LoadError::Parse { line: 4, .. }
+    ));
+    // Número inválido no corpo, contado depois das linhas do cabeçalho
+    assert!(matches!(
+        error(&ASCII.replace("1 1 0 0 0 1", "1 x 0 0 0 1")),
+        LoadError::Parse { line: 19, .. }
+    ));
+    // Corpo com menos linhas que o cabeçalho declara
+    assert!(matches!(
+        error(ASCII.trim_end_matches("4 0 1 2 3\n")),
+        LoadError::UnexpectedEof { .. }
+    ));
+    assert!(matches!(
+        error(&ASCII.replace("4 0 1 2 3", "3 0 1 4")),
+        LoadError::IndexOutOfRange { index: 4, count: 4 }
+    ));
+    assert!(matches!(
+        error(&ASCII.replace("vertex_indices", "vertices")),
+        LoadError::MissingData(_)
+    ));
+}
@@ -0,0 +1,83 @@
+//! Raízes dos polinômios, comparadas com as de polinômios montados a partir delas.
+
+use raycaster::solvers::{solve_cubic, solve_quadratic, solve_quartic};
+
+/// Coeficientes (do maior grau pro menor) de scale * (t - r1) * (t - r2) * ...
+fn from_roots(scale: f64, roots: &[f64]) -> Vec<f64> {
+    roots.iter().fold(vec![scale], |coefficients, &root| {
+        // Multiplica por (t - root)
+        let mut next = coefficients.clone();
+        next.push(0.0);
+        for (i, k) in coefficients.iter().enumerate() {
+            next[i + 1] -= root * k;
+        }
+        next
+    })
+}
+
+fn assert_roots(found: &[f64], expected: &[f64], tolerance: f64) {
+    assert_eq!(found.len(), expected.len(), "{found:?} != {expected:?}");
+    for (found, expected) in found.iter().zip(expected) {
+        assert!(
+            (found - expected).abs() <= tolerance * expected.abs().max(1.0),
+            "{found} != {expected}"
+        );
+    }
+}
+
+#[test]
+fn quartic_with_four_real_roots() {
+    for roots in [
+        [1.0, 2.0, 3.0, 4.0],
+        [-5.0, -0.5, 0.25, 10.0],
+        [-2.0, -1.0, 1.0, 2.0],
+        [0.001, 0.002, 100.0, 1000.0],
+    ] {
+        // Os coeficientes multiplicados por qualquer número têm as mesmas raízes
+        for scale in [1.0, -3.0, 1e-9, 1e9] {
+            let [a, b, c, d, e] = from_roots(scale, &roots)[..] else {
+                unreachable!()
+            };
+            assert_roots(&solve_quartic(a, b, c, d, e), &roots, 1e-8);
+        }
+    }
+}
+
+#[test]
+fn quartic_with_fewer_real_roots() {
+    // (t² + 1)(t - 1)(t + 3) = t⁴ + 2t³ - 2t² + 2t - 3
+    assert_roots(
+        &solve_quartic(1.0, 2.0, -2.0, 2.0, -3.0),
+        &[-3.0, 1.0],
+        1e-12,
+    );
+    // Pares de raízes complexas: (t² + 1)(t² + 4)
+    assert_roots(&solve_quartic(1.0, 0.0, 5.0, 0.0, 4.0), &[], 0.0);
+    // Biquadrada: (t² - 1)(t² - 9)
+    assert_roots(
+        &solve_quartic(1.0, 0.0, -10.0, 0.0, 9.0),
+        &[-3.0, -1.0, 1.0, 3.0],
+        1e-12,
+    );
+    // Raiz quádrupla: (t - 1)⁴
+    assert_roots(&solve_quartic(1.0, -4.0, 6.0, -4.0, 1.0), &[1.0; 4], 1e-3);
+}
+
+#[test]
+fn degenerate_leading_coefficients_lower_the_degree() {
+    // Coeficiente de maior grau desprezível perto dos outros: (t - 1)(t - 2)(t - 3)
+    let [a, b, c, d] = from_roots(1.0, &[1.0, 2.0, 3.0])[..] else {
+        unreachable!()
+    };
+    assert_roots(&solve_quartic(1e-15, a, b, c, d), &[1.0, 2.0, 3.0], 1e-9);
+    assert_roots(&solve_cubic(1e-20, 1.0, -3.0, 2.0), &[1.0, 2.0], 1e-12);
+    assert_eq!(solve_quadratic(0.0, 2.0, -4.0), Some([2.0, 2.0]));
+    assert_eq!(solve_quadratic(0.0, 0.0, 1.0), None);
+
+    // Polinômios inteiros em escala pequena continuam com o mesmo grau
+    let [a, b, c] = from_roots(1e-14, &[-1.0, 4.0])[..] else {
+        unreachable!()
+    };
+    let roots = solve_quadratic(a, b, c).unwrap();
+    assert_roots(&roots, &[-1.0, 4.0], 1e-12);
+}
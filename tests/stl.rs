@@ -0,0 +1,109 @@
+//! Leitura de arquivos .stl (ASCII e binários) a partir de conteúdos montados no próprio teste.
+
+use glam::DVec3;
+use raycaster::{
+    Material,
+    loaders::{LoadError, parse_stl},
+};
+
+/// Duas facetas: uma com normal, outra com normal nula (que é calculada)
+const ASCII: &str = "solid teste
+  facet normal 0 0 2
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 0 1 0
+    endloop
+  endfacet
+  facet normal 0 0 0
+    outer loop
+      vertex 0 0 0
+      vertex 0 1 0
+      vertex 0 0 1
+    endloop
+  endfacet
+endsolid teste
+";
+
+/// As mesmas facetas de `ASCII` num .stl binário. O cabeçalho começa com "solid", como o de
+/// alguns exportadores, pra testar que o formato é detectado pelo tamanho.
+fn binary() -> Vec<u8> {
+    let triangles = [
+        [[0.0, 0.0, 2.0], [0.0; 3], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        [[0.0; 3], [0.0; 3], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+    ];
+    let mut bytes = b"solid binario".to_vec();
+    bytes.resize(80, b' ');
+    bytes.extend((triangles.len() as u32).to_le_bytes());
+    for triangle in triangles {
+        for value in triangle.as_flattened() {
+            bytes.extend((*value as f32).to_le_bytes());
+        }
+        // Atributos
+        bytes.extend([0, 0]);
+    }
+    bytes
+}
+
+#[test]
+fn ascii_and_binary_give_the_same_flat_mesh() {
+    let ascii = parse_stl(ASCII.as_bytes(), Material::WHITE).unwrap();
+    let binary = parse_stl(&binary(), Material::WHITE).unwrap();
+    for mesh in [&ascii, &binary] {
+        // Os triângulos não compartilham vértices
+        assert_eq!(mesh.vertices().len(), 6);
+        assert_eq!(mesh.faces(), &[[0, 1, 2], [3, 4, 5]]);
+        // Normal do arquivo (normalizada) e normal calculada pela ordem dos vértices
+        assert_eq!(&mesh.normals[..3], &[DVec3::Z; 3]);
+        assert_eq!(&mesh.normals[3..], &[DVec3::X; 3]);
+    }
+    assert_eq!(ascii.vertices(), binary.vertices());
+}
+
+#[test]
+fn ascii_facets_with_more_vertices_are_split() {
+    let source = "solid
+        facet normal 0 0 1
+          outer loop
+            vertex 0 0 0
+            vertex 1 0 0
+            vertex 1 1 0
+            vertex 0 1 0
+          endloop
+        endfacet
+        endsolid
+    ";
+    let mesh = parse_stl(source.as_bytes(), Material::WHITE).unwrap();
+    assert_eq!(mesh.faces().len(), 2);
+    assert_eq!(mesh.vertices()[5], DVec3::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn malformed_files_are_errors() {
+    let error = |source: &str| parse_stl(source.as_bytes(), Material::WHITE).err().unwrap();
+
+    assert!(matches!(
+        error(&ASCII.replace("vertex 1 0 0", "vertex 1 x 0")),
+        LoadError::Parse { line: 5, .. }
+    ));
+    assert!(matches!(
+        error(&ASCII.replace("endloop", "fim")),
+        LoadError::Parse { line: 7, .. }
+    ));
+    assert!(matches!(
+        error("solid\nfacet normal 0 0 1\nvertex 0 0 0\n"),
+        LoadError::UnexpectedEof { .. }
+    ));
+    assert!(matches!(
+        error("solid\nfacet\nvertex 0 0 0\nvertex 1 0 0\nendfacet\n"),
+        LoadError::Parse { line: 5, .. }
+    ));
+
+    // Binário com menos triângulos do que o cabeçalho diz
+    let mut bytes = binary();
+    bytes.truncate(bytes.len() - 10);
+    assert!(matches!(
+        parse_stl(&bytes, Material::WHITE).err().unwrap(),
+        LoadError::UnexpectedEof { .. }
+    ));
+}
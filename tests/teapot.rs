@@ -0,0 +1,80 @@
+//! Leitura de arquivos de patches no formato do bule de Newell a partir de textos no
+//! próprio teste.
+
+use glam::DVec3;
+use raycaster::{
+    Material, Ray,
+    loaders::{LoadError, parse_teapot},
+};
+
+/// Arquivo com `patches` (linhas de 16 índices) e uma grade plana de 4x4 pontos no plano
+/// z = 0, com x e y entre 0 e 1. O ponto de índice 4*i + j + 1 está em (j/3, i/3, 0).
+fn source(patches: &[&str]) -> String {
+    let mut source = format!("{}\n", patches.len());
+    for patch in patches {
+        source += patch;
+        source += "\n";
+    }
+    source += "16\n";
+    for i in 0..4 {
+        for j in 0..4 {
+            source += &format!("{}, {}, 0\n", j as f64 / 3.0, i as f64 / 3.0);
+        }
+    }
+    source
+}
+
+/// A grade na ordem em que os pontos foram escritos, com vírgulas e espaços misturados
+const GRID: &str = "1,2,3,4, 5,6,7,8,9 10 11 12,13,14,15,16";
+/// A mesma grade, de cabeça pra baixo (as linhas da grade na ordem inversa)
+const FLIPPED: &str = "13,14,15,16,9,10,11,12,5,6,7,8,1,2,3,4";
+
+#[test]
+fn patches_use_the_indexed_control_points() {
+    let patches = parse_teapot(&source(&[GRID, FLIPPED]), Material::WHITE).unwrap();
+    assert_eq!(patches.len(), 2);
+
+    let control_points = patches[0].control_points();
+    assert_eq!(control_points[0][0], DVec3::ZERO);
+    assert_eq!(control_points[0][3], DVec3::X);
+    assert_eq!(control_points[3][0], DVec3::Y);
+    assert_eq!(patches[1].control_points()[0][0], DVec3::Y);
+
+    // A grade é plana e uniforme, então o patch é o próprio quadrado (u em x, v em y)
+    let point = patches[0].point_at(0.25, 0.5);
+    assert!((point - DVec3::new(0.25, 0.5, 0.0)).length() < 1e-12);
+    for patch in &patches {
+        let ray = Ray::new(DVec3::new(0.3, 0.6, 1.0), -DVec3::Z);
+        let intersection = patch.intersects(&ray).unwrap();
+        assert!((intersection.t - 1.0).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn malformed_files_are_errors() {
+    let error = |source: &str| parse_teapot(source, Material::WHITE).err().unwrap();
+
+    // Patch com 15 índices, com índice 0 e com índice depois do último ponto
+    assert!(matches!(
+        error(&source(&[GRID, "1,2,3,4,5,6,7,8,9,10,11,12,13,14,15"])),
+        LoadError::Parse { line: 3, .. }
+    ));
+    assert!(matches!(
+        error(&source(&[&GRID.replace("16", "0")])),
+        LoadError::Parse { line: 2, .. }
+    ));
+    assert!(matches!(
+        error(&source(&[GRID, &FLIPPED.replace("16", "17")])),
+        LoadError::Parse { line: 3, .. }
+    ));
+    // Ponto com 2 coordenadas, e arquivo sem todos os pontos
+    assert!(matches!(
+        error(&source(&[GRID]).replace("0, 0, 0\n", "0, 0\n")),
+        LoadError::Parse { line: 4, .. }
+    ));
+    assert!(matches!(
+        error(&format!("1\n{GRID}\n16\n0 0 0\n")),
+        LoadError::UnexpectedEof { .. }
+    ));
+    assert!(matches!(error("dois\n"), LoadError::Parse { line: 1, .. }));
+}